@@ -1,6 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::{ToTokens, quote};
-use syn::{Attribute, Expr, Lit, Meta};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, Meta, Token};
 
 /// Parse syn::Meta into our AttributeMeta representation
 fn parse_meta_to_attribute_meta(meta: &Meta) -> TokenStream {
@@ -17,13 +18,15 @@ fn parse_meta_to_attribute_meta(meta: &Meta) -> TokenStream {
             let path_str = list.path.to_token_stream().to_string();
             let tokens_str = list.tokens.to_string();
 
-            // Try to parse the list contents
-            if let Ok(nested_meta) = syn::parse2::<Meta>(list.tokens.clone()) {
-                let nested = parse_meta_to_attribute_meta(&nested_meta);
+            // Try to parse the list contents as comma-separated metas
+            if let Ok(nested_metas) =
+                list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            {
+                let nested = nested_metas.iter().map(parse_meta_to_attribute_meta);
                 quote! {
                     visit_rs::metadata::AttributeMeta::List {
                         path: #path_str,
-                        items: &[#nested],
+                        items: &[#(#nested),*],
                     }
                 }
             } else {
@@ -106,3 +109,19 @@ pub fn extract_all_meta(attrs: &[Attribute]) -> Vec<TokenStream> {
         .collect()
 }
 
+/// A `&'static [AttributeMeta]` expression for `metas`, as returned by
+/// [`extract_all_meta`]. Only expanded when visit-rs is built with `meta`, as the
+/// `metadata` of a `visit_rs::__with_metadata!` struct literal
+pub fn metadata_ref(metas: &[TokenStream]) -> TokenStream {
+    if metas.is_empty() {
+        return quote! { &[] };
+    }
+    let count = metas.len();
+    quote! {
+        {
+            const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
+            &META
+        }
+    }
+}
+
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use quote::ToTokens;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, DeriveInput, Expr, ExprLit, Field, Lit, Meta, Path, Token, WherePredicate};
+
+use crate::helpers::visit_items;
+use crate::keys;
+use crate::with;
+
+/// Which family of generated impls a where clause belongs to, selecting the
+/// `#[visit(bound(<kind> = "..."))]` override that applies to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundKind {
+    Visit,
+    VisitAsync,
+    VisitAsyncLocal,
+    VisitMut,
+    VisitOwned,
+    Build,
+    DynVisit,
+}
+
+impl BoundKind {
+    /// The kind of the impls bounding fields by `trait_path`, e.g. `visit_rs::VisitAsync`
+    pub fn of_trait(trait_path: &Path) -> Self {
+        let trait_name = trait_path.segments.last().map(|segment| &segment.ident);
+        match trait_name.map(ToString::to_string).as_deref() {
+            Some("Visit") => BoundKind::Visit,
+            Some("VisitAsync") => BoundKind::VisitAsync,
+            Some("VisitAsyncLocal") => BoundKind::VisitAsyncLocal,
+            Some("VisitMut") => BoundKind::VisitMut,
+            Some("VisitOwned") => BoundKind::VisitOwned,
+            Some("Build") => BoundKind::Build,
+            Some("DynVisit") => BoundKind::DynVisit,
+            _ => unreachable!("no bound kind for {}", trait_path.to_token_stream()),
+        }
+    }
+
+    /// The kind whose functions a field visited `with` a module goes through in the
+    /// impls of this kind, as local impls await `visit_async`
+    pub fn visited_with(self) -> Self {
+        match self {
+            BoundKind::VisitAsyncLocal => BoundKind::VisitAsync,
+            kind => kind,
+        }
+    }
+
+    const ALL: [(&'static str, BoundKind); 7] = [
+        ("visit", BoundKind::Visit),
+        ("visit_async", BoundKind::VisitAsync),
+        ("visit_async_local", BoundKind::VisitAsyncLocal),
+        ("visit_mut", BoundKind::VisitMut),
+        ("visit_owned", BoundKind::VisitOwned),
+        ("build", BoundKind::Build),
+        ("dyn_visit", BoundKind::DynVisit),
+    ];
+}
+
+/// The `bound` overrides from one container or field
+#[derive(Default)]
+struct Bounds {
+    all: Option<Vec<WherePredicate>>,
+    by_kind: Vec<(BoundKind, Vec<WherePredicate>)>,
+}
+
+impl Bounds {
+    fn get(self, kind: BoundKind) -> Option<Vec<WherePredicate>> {
+        self.by_kind
+            .into_iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, bound)| bound)
+            .or(self.all)
+    }
+
+    /// As [`Bounds::get`], falling back to the bound for the kind whose functions
+    /// a field visited `with` a module goes through
+    fn get_with(mut self, kind: BoundKind) -> Option<Vec<WherePredicate>> {
+        match self.by_kind.iter().position(|(k, _)| *k == kind) {
+            Some(pos) => Some(self.by_kind.swap_remove(pos).1),
+            None => self.get(kind.visited_with()),
+        }
+    }
+}
+
+fn parse_predicates(value: &Expr) -> Result<Vec<WherePredicate>, syn::Error> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(s), ..
+    }) = value
+    else {
+        return Err(syn::Error::new_spanned(
+            value,
+            "expected a string of where-clause predicates, e.g. `bound = \"T: Clone\"`",
+        ));
+    };
+    let predicates = s.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// Parse `#[visit(bound = "...")]` and `#[visit(bound(visit = "...", ...))]`
+fn parse_bounds(attrs: &[Attribute]) -> Result<Bounds, syn::Error> {
+    let mut bounds = Bounds::default();
+    for meta in visit_items(attrs) {
+        match meta {
+            Meta::NameValue(nv) if nv.path.is_ident("bound") => {
+                bounds.all = Some(parse_predicates(&nv.value)?);
+            }
+            Meta::List(list) if list.path.is_ident("bound") => {
+                let items = list.parse_args_with(
+                    Punctuated::<syn::MetaNameValue, Token![,]>::parse_terminated,
+                )?;
+                for nv in items {
+                    let Some((_, kind)) = BoundKind::ALL
+                        .iter()
+                        .find(|(name, _)| nv.path.is_ident(name))
+                    else {
+                        let expected: Vec<_> =
+                            BoundKind::ALL.iter().map(|(name, _)| *name).collect();
+                        let kind = nv.path.to_token_stream().to_string();
+                        return Err(syn::Error::new_spanned(
+                            &nv.path,
+                            match keys::did_you_mean(&kind, expected.iter().copied()) {
+                                Some(name) => {
+                                    format!("unknown bound kind `{kind}`, did you mean `{name}`?")
+                                }
+                                None => format!(
+                                    "unknown bound kind, expected one of: {}",
+                                    expected.join(", ")
+                                ),
+                            },
+                        ));
+                    };
+                    bounds.by_kind.push((*kind, parse_predicates(&nv.value)?));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(bounds)
+}
+
+/// Push the where-clause predicates for `fields` onto `predicates`
+///
+/// A container-level `bound` for `kind` replaces every inferred predicate. Otherwise
+/// each field contributes its own `bound` for `kind` if it has one, or the
+/// predicates `inferred` from it. A field visited `with` a function needs the
+/// inferred bounds on the wrappers around it either way, so there its `bound`
+/// adds what the function needs instead, and the local impls fall back to the
+/// `bound` for `visit_async`, whose function they call. Duplicates are pushed
+/// only once.
+pub fn push_field_bounds<'a>(
+    predicates: &mut Punctuated<WherePredicate, Token![,]>,
+    ast: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a Field>,
+    kind: BoundKind,
+    mut inferred: impl FnMut(&'a Field) -> Vec<WherePredicate>,
+) -> Result<(), syn::Error> {
+    if let Some(bound) = parse_bounds(&ast.attrs)?.get(kind) {
+        predicates.extend(bound);
+        return Ok(());
+    }
+    let mut seen = HashSet::new();
+    for field in fields {
+        let bounds = parse_bounds(&field.attrs)?;
+        let field_predicates = if with::is_visited_with(field, kind) {
+            let mut bound = bounds.get_with(kind).unwrap_or_default();
+            bound.extend(inferred(field));
+            bound
+        } else {
+            bounds.get(kind).unwrap_or_else(|| inferred(field))
+        };
+        for predicate in field_predicates {
+            if seen.insert(predicate.to_token_stream().to_string()) {
+                predicates.push(predicate);
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,17 +1,21 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::quote;
 use std::collections::HashSet;
-use syn::{DataEnum, DeriveInput, Fields, Ident};
+use syn::{DataEnum, DeriveInput, Fields, Ident, WherePredicate, parse_quote};
 
 use crate::attrs;
+use crate::bounds::{BoundKind, push_field_bounds};
 use crate::helpers::{
-    get_field_rename, get_rename_all_attribute, get_rename_attribute, get_variant_rename,
+    get_field_rename, get_rename_all_attribute, get_rename_attribute, get_variant_aliases,
+    get_variant_fields_rules, get_variant_rename, has_visit_flag, source_text,
 };
+use crate::{concurrent_stream, field_infos, with, yield_visit};
 
 pub fn derive_all_variant_traits(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
+    with::check_fields(data.variants.iter().flat_map(|variant| &variant.fields))?;
     let enum_info = derive_enum_info(ast, data)?;
     let visit_variant = derive_visit_variant(ast, data)?;
     let visit_variants_static = derive_visit_variants_static(ast, data)?;
@@ -24,8 +28,19 @@ pub fn derive_all_variant_traits(
     let visit_variant_fields_covered_async = derive_visit_variant_fields_covered_async(ast, data)?;
     let visit_variant_fields_static_async = derive_visit_variant_fields_static_async(ast, data)?;
     let visit_variant_fields_named_async = derive_visit_variant_fields_named_async(ast, data)?;
+    let visit_variant_fields_async_local = derive_visit_variant_fields_async_local(ast, data)?;
+    let visit_variant_fields_named_async_local =
+        derive_visit_variant_fields_named_async_local(ast, data)?;
+    let visit_variant_fields_pathed = derive_visit_variant_fields_pathed(ast, data)?;
+    let visit_variant_fields_concurrent = derive_visit_variant_fields_concurrent(ast, data)?;
     let visit_variant_fields_static_named_async =
         derive_visit_variant_fields_static_named_async(ast, data)?;
+    let visit_variant_fields_mut = derive_visit_variant_fields_mut(ast, data)?;
+    let visit_variant_fields_named_mut = derive_visit_variant_fields_named_mut(ast, data)?;
+    let visit_variant_fields_owned = derive_visit_variant_fields_owned(ast, data)?;
+    let from_variant_fields = derive_from_variant_fields(ast, data)?;
+    let dyn_visit_variant_fields = derive_dyn_visit_variant_fields(ast, data)?;
+    let unit_variants = derive_unit_variants(ast, data)?;
 
     Ok(quote! {
         #enum_info
@@ -40,7 +55,17 @@ pub fn derive_all_variant_traits(
         #visit_variant_fields_covered_async
         #visit_variant_fields_static_async
         #visit_variant_fields_named_async
+        #visit_variant_fields_async_local
+        #visit_variant_fields_named_async_local
+        #visit_variant_fields_pathed
+        #visit_variant_fields_concurrent
         #visit_variant_fields_static_named_async
+        #visit_variant_fields_mut
+        #visit_variant_fields_named_mut
+        #visit_variant_fields_owned
+        #from_variant_fields
+        #dyn_visit_variant_fields
+        #unit_variants
     })
 }
 
@@ -54,19 +79,7 @@ fn derive_enum_info(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, s
     // Extract enum-level metadata
     let enum_meta = attrs::extract_all_meta(&ast.attrs);
 
-    let enum_meta_ref = if !enum_meta.is_empty() {
-        let count = enum_meta.len();
-        quote! {
-            if cfg!(feature = "meta") {
-                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#enum_meta),*];
-                &META
-            } else {
-                &[]
-            }
-        }
-    } else {
-        quote! { &[] }
-    };
+    let enum_meta_ref = attrs::metadata_ref(&enum_meta);
 
     // Get rename_all rule for variants
     let rename_all_rule = get_rename_all_attribute(ast);
@@ -78,6 +91,13 @@ fn derive_enum_info(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, s
         .map(|variant| get_variant_rename(variant, rename_all_rule))
         .collect();
 
+    // The other names each variant is looked up by
+    let variant_aliases: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| get_variant_aliases(variant, rename_all_rule))
+        .collect();
+
     // Validate uniqueness
     let mut seen_names = HashSet::new();
     for (idx, renamed_name) in renamed_variants.iter().enumerate() {
@@ -92,6 +112,17 @@ fn derive_enum_info(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, s
             ));
         }
     }
+    for (idx, aliases) in variant_aliases.iter().enumerate() {
+        for alias in aliases {
+            if !seen_names.insert(alias) {
+                let original_name = &data.variants[idx].ident;
+                return Err(syn::Error::new_spanned(
+                    original_name,
+                    format!("Variant alias '{}' is already the name of a variant", alias),
+                ));
+            }
+        }
+    }
 
     // Extract variant-level metadata
     let variant_metas: Vec<_> = data
@@ -102,102 +133,112 @@ fn derive_enum_info(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, s
 
     let variant_meta_refs: Vec<_> = variant_metas
         .iter()
-        .map(|metas| {
-            if !metas.is_empty() {
-                let count = metas.len();
-                quote! {
-                    if cfg!(feature = "meta") {
-                        const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                        &META
-                    } else {
-                        &[]
-                    }
-                }
-            } else {
-                quote! { &[] }
-            }
-        })
+        .map(|metas| attrs::metadata_ref(metas))
+        .collect();
+
+    let repr_lists: Vec<_> = ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .filter_map(|attr| attr.meta.require_list().ok())
         .collect();
+    let is_u128 = repr_lists.iter().any(|list| {
+        list.tokens
+            .clone()
+            .into_iter()
+            .any(|token| matches!(token, TokenTree::Ident(ident) if ident == "u128"))
+    });
+    let repr: Vec<_> = repr_lists
+        .iter()
+        .map(|list| source_text(&list.tokens))
+        .collect();
+    let repr = if repr.is_empty() {
+        quote! { None }
+    } else {
+        let repr = repr.join(", ");
+        quote! { Some(#repr) }
+    };
 
     // Generate StructInfoData for each variant
-    let variant_infos = data.variants.iter().enumerate().zip(&renamed_variants).map(
-        |((idx, _variant), renamed_name)| {
-            let variant = &data.variants[idx];
-            let named_fields = matches!(variant.fields, Fields::Named(_));
-            let field_count = variant.fields.iter().count();
-            let metadata_ref = &variant_meta_refs[idx];
+    let variant_infos: Vec<_> = data
+        .variants
+        .iter()
+        .zip(&renamed_variants)
+        .zip(&variant_meta_refs)
+        .zip(discriminants(data, is_u128))
+        .enumerate()
+        .map(
+            |(index, (((variant, renamed_name), metadata_ref), value))| {
+                let named_fields = matches!(variant.fields, Fields::Named(_));
+                let field_count = variant.fields.iter().count();
+                let fields = field_infos(&variant.fields, get_variant_fields_rules(ast, variant));
+                let discriminant = match &variant.discriminant {
+                    Some((_, expr)) => {
+                        let expr = source_text(expr);
+                        quote! { Some(#expr) }
+                    }
+                    None => quote! { None },
+                };
 
-            quote! {
-                visit_rs::StructInfoData {
-                    name: #renamed_name,
-                    named_fields: #named_fields,
-                    field_count: #field_count,
-                    #[cfg(feature = "meta")]
-                    metadata: #metadata_ref,
+                quote! {
+                    visit_rs::__with_metadata!(visit_rs::StructInfoData {
+                        name: #renamed_name,
+                        named_fields: #named_fields,
+                        field_count: #field_count,
+                        fields: #fields,
+                        variant: Some(visit_rs::VariantInfo {
+                            index: #index,
+                            discriminant: #discriminant,
+                            discriminant_value: #value,
+                        }),
+                    } metadata: #metadata_ref)
                 }
-            }
-        },
-    );
+            },
+        )
+        .collect();
 
-    // Generate variant_info match arms
-    let variant_info_arms = data.variants.iter().enumerate().zip(&renamed_variants).map(
-        |((idx, variant), renamed_name)| {
+    let patterns: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| {
             let variant_name = &variant.ident;
-            let named_fields = matches!(variant.fields, Fields::Named(_));
-            let field_count = variant.fields.iter().count();
-
-            let pattern = match &variant.fields {
+            match &variant.fields {
                 Fields::Named(_) => quote! { Self::#variant_name { .. } },
-                Fields::Unnamed(_) => {
-                    let placeholders = (0..field_count).map(|_| quote! { _ });
-                    quote! { Self::#variant_name(#(#placeholders),*) }
-                }
+                Fields::Unnamed(_) => quote! { Self::#variant_name(..) },
                 Fields::Unit => quote! { Self::#variant_name },
-            };
-
-            let metadata_ref = &variant_meta_refs[idx];
+            }
+        })
+        .collect();
 
+    // Generate variant_info match arms
+    let variant_info_arms = patterns
+        .iter()
+        .zip(&variant_infos)
+        .map(|(pattern, variant_info)| {
             quote! {
-                #pattern => visit_rs::StructInfoData {
-                    name: #renamed_name,
-                    named_fields: #named_fields,
-                    field_count: #field_count,
-                    #[cfg(feature = "meta")]
-                    metadata: #metadata_ref,
-                }
+                #pattern => #variant_info
             }
-        },
-    );
+        });
+    let variant_indexes = 0..data.variants.len();
 
     // Generate variant_info_by_name match arms
     let variant_by_name_arms = renamed_variants
         .iter()
-        .enumerate()
-        .map(|(idx, renamed_name)| {
-            let variant = &data.variants[idx];
-            let named_fields = matches!(variant.fields, Fields::Named(_));
-            let field_count = variant.fields.iter().count();
-            let metadata_ref = &variant_meta_refs[idx];
-
+        .zip(&variant_aliases)
+        .zip(&variant_infos)
+        .map(|((renamed_name, aliases), variant_info)| {
             quote! {
-                #renamed_name => Some(visit_rs::StructInfoData {
-                    name: #renamed_name,
-                    named_fields: #named_fields,
-                    field_count: #field_count,
-                    #[cfg(feature = "meta")]
-                    metadata: #metadata_ref,
-                })
+                #renamed_name #(| #aliases)* => Some(#variant_info)
             }
         });
 
     Ok(quote! {
         impl #impl_generics visit_rs::EnumInfo for #ident #ty_generics #where_clause {
-            const DATA: visit_rs::EnumInfoData = visit_rs::EnumInfoData {
+            const DATA: visit_rs::EnumInfoData = visit_rs::__with_metadata!(visit_rs::EnumInfoData {
                 name: #name,
                 variant_count: #variant_count,
-                #[cfg(feature = "meta")]
-                metadata: #enum_meta_ref,
-            };
+                repr: #repr,
+            } metadata: #enum_meta_ref);
 
             fn variants() -> impl IntoIterator<Item = visit_rs::StructInfoData> + Send + Sync + 'static {
                 [#(#variant_infos),*]
@@ -215,27 +256,236 @@ fn derive_enum_info(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, s
                     _ => None
                 }
             }
+
+            fn variant_index(&self) -> usize {
+                match self {
+                    #(#patterns => #variant_indexes,)*
+                }
+            }
         }
     })
 }
 
-fn derive_visit_variant(ast: &DeriveInput, _data: &DataEnum) -> Result<TokenStream, syn::Error> {
+/// The variants of an enum whose variants all have no fields as a set of values,
+/// each part opt-in: `UnitVariants` for `#[visit(unit_variants)]`, and `FromStr`
+/// and `Display` through the names for `#[visit(from_str)]` and `#[visit(display)]`
+fn derive_unit_variants(ast: &DeriveInput, data: &DataEnum) -> Result<TokenStream, syn::Error> {
+    let flags: Vec<_> = ["unit_variants", "from_str", "display"]
+        .into_iter()
+        .filter(|flag| has_visit_flag(&ast.attrs, flag))
+        .collect();
+    if flags.is_empty() {
+        return Ok(TokenStream::new());
+    }
+    if let Some(variant) = data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(syn::Error::new_spanned(
+            &variant.fields,
+            format!(
+                "`{}` requires every variant to have no fields",
+                flags.join("`, `")
+            ),
+        ));
+    }
+
     let ident = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let rename_all_rule = get_rename_all_attribute(ast);
+
+    let variants: Vec<_> = data.variants.iter().map(|variant| &variant.ident).collect();
+    let names: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| get_variant_rename(variant, rename_all_rule))
+        .collect();
+    let by_name = {
+        let arms = data.variants.iter().zip(&names).map(|(variant, name)| {
+            let variant_name = &variant.ident;
+            let aliases = get_variant_aliases(variant, rename_all_rule);
+            quote! {
+                #name #(| #aliases)* => Some(Self::#variant_name)
+            }
+        });
+        quote! {
+            match name {
+                #(#arms,)*
+                _ => None
+            }
+        }
+    };
+    let variant_name = quote! {
+        match *self {
+            #(Self::#variants => #names,)*
+        }
+    };
+
+    let unit_variants = flags.contains(&"unit_variants").then(|| {
+        quote! {
+            impl #impl_generics visit_rs::UnitVariants for #ident #ty_generics #where_clause {
+                const ALL: &'static [Self] = &[#(Self::#variants),*];
+
+                fn all_values() -> impl Iterator<Item = Self> + Send + Sync + 'static {
+                    [#(Self::#variants),*].into_iter()
+                }
+
+                fn variant_by_name(name: &str) -> Option<Self> {
+                    #by_name
+                }
+
+                fn as_variant_name(&self) -> &'static str {
+                    #variant_name
+                }
+            }
+        }
+    });
+    let from_str = flags.contains(&"from_str").then(|| {
+        quote! {
+            impl #impl_generics ::core::str::FromStr for #ident #ty_generics #where_clause {
+                type Err = visit_rs::ParseVariantError;
+
+                fn from_str(name: &str) -> Result<Self, Self::Err> {
+                    let variant: Option<Self> = #by_name;
+                    variant.ok_or_else(|| visit_rs::ParseVariantError {
+                        enum_name: <Self as visit_rs::EnumInfo>::DATA.name,
+                        name: name.to_string(),
+                    })
+                }
+            }
+        }
+    });
+    let display = flags.contains(&"display").then(|| {
+        quote! {
+            impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(#variant_name)
+                }
+            }
+        }
+    });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariant<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            for<'a> visit_rs::Variant<'a, Self>: visit_rs::Visit<__visit_rs__V>,
-        {
-            fn visit_variant(&self, visitor: &mut __visit_rs__V) -> <__visit_rs__V as visit_rs::Visitor>::Result {
-                visit_rs::Variant {
-                    info: self.variant_info(),
-                    value: self,
+        #unit_variants
+        #from_str
+        #display
+    })
+}
+
+/// The `discriminant_value` of each variant. An enum without fields can be cast
+/// in a const; otherwise the value is only known where it counts up from an
+/// integer literal, or from zero. Under `repr(u128)`, values above `i128::MAX`
+/// are `None`
+fn discriminants(data: &DataEnum, is_u128: bool) -> Vec<TokenStream> {
+    if data
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        return data
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.ident;
+                if is_u128 {
+                    quote! {
+                        if Self::#variant_name as u128 <= i128::MAX as u128 {
+                            Some(Self::#variant_name as i128)
+                        } else {
+                            None
+                        }
+                    }
+                } else {
+                    quote! { Some(Self::#variant_name as i128) }
                 }
-                .visit(visitor)
+            })
+            .collect();
+    }
+    let mut next = Some(0i128);
+    data.variants
+        .iter()
+        .map(|variant| {
+            let value = match &variant.discriminant {
+                Some((_, expr)) => int_literal(expr),
+                None => next,
+            };
+            next = value.and_then(|value| value.checked_add(1));
+            match value {
+                Some(value) => quote! { Some(#value) },
+                None => quote! { None },
+            }
+        })
+        .collect()
+}
+
+/// The value of an integer literal, possibly negated
+fn int_literal(expr: &syn::Expr) -> Option<i128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_literal(expr).map(|value: i128| -value),
+        syn::Expr::Group(group) => int_literal(&group.expr),
+        syn::Expr::Paren(paren) => int_literal(&paren.expr),
+        _ => None,
+    }
+}
+
+/// The impl header for `trait_path`, with the visitor bounded by
+/// `visitor_predicates` and `fields` by their `bound` overrides or the predicates
+/// `inferred` from them
+fn make_variant_impl<'a>(
+    ast: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    trait_path: TokenStream,
+    visitor_predicates: Vec<WherePredicate>,
+    kind: BoundKind,
+    inferred: impl FnMut(&'a syn::Field) -> Vec<WherePredicate>,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.extend(visitor_predicates);
+    push_field_bounds(predicates, ast, fields, kind, inferred)?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #trait_path<__visit_rs__V> for #ident #ty_generics #where_clause
+    })
+}
+
+fn derive_visit_variant(ast: &DeriveInput, _data: &DataEnum) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        [],
+        quote! { visit_rs::VisitVariant },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor },
+            parse_quote! { for<'a> visit_rs::Variant<'a, Self>: visit_rs::Visit<__visit_rs__V> },
+        ],
+        BoundKind::Visit,
+        |_| Vec::new(),
+    )?;
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_variant(&self, visitor: &mut __visit_rs__V) -> <__visit_rs__V as visit_rs::Visitor>::Result {
+                visit_rs::Visit::visit(
+                    &visit_rs::Variant {
+                        info: visit_rs::EnumInfo::variant_info(self),
+                        value: self,
+                    },
+                    visitor,
+                )
             }
         }
     })
@@ -245,24 +495,30 @@ fn derive_visit_variants_static(
     ast: &DeriveInput,
     _data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let impl_t = make_variant_impl(
+        ast,
+        [],
+        quote! { visit_rs::VisitVariantsStatic },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor },
+            parse_quote! { for<'a> visit_rs::Variant<'a, visit_rs::Static<Self>>: visit_rs::Visit<__visit_rs__V> },
+        ],
+        BoundKind::Visit,
+        |_| Vec::new(),
+    )?;
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantsStatic<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            for<'a> visit_rs::Variant<'a, visit_rs::Static<Self>>: visit_rs::Visit<__visit_rs__V>,
-        {
+        #impl_t {
             fn visit_variants_static<'a>(visitor: &'a mut __visit_rs__V) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
-                Self::variants().into_iter().map(|info| {
-                    visit_rs::Variant {
-                        info,
-                        value: visit_rs::Static::new_ref(),
-                    }
-                    .visit(visitor)
-                })
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(<Self as visit_rs::EnumInfo>::variants().into_iter().map(|info| {
+                    visit_rs::Visit::visit(
+                        &visit_rs::Variant {
+                            info,
+                            value: visit_rs::Static::new_ref(),
+                        },
+                        visitor,
+                    )
+                }))
             }
         }
     })
@@ -272,20 +528,18 @@ fn derive_visit_variant_fields(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    // Collect all unique field types for trait bounds
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFields },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::Visit,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { #ty: visit_rs::Visit<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::Visit, false, None)
+                .unwrap_or_else(|| vec![parse_quote! { #ty: visit_rs::Visit<__visit_rs__V> }])
+        },
+    )?;
 
     // Generate match arms for each variant
     let variant_arms = data.variants.iter().map(|variant| {
@@ -294,10 +548,11 @@ fn derive_visit_variant_fields(
         match &variant.fields {
             Fields::Named(fields) => {
                 let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_matches = (0..field_names.len()).map(|idx| {
-                    let field_name = &field_names[idx];
+                let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
+                    let field_name = &field.ident;
+                    let value = with::value(field, quote! { #field_name }, BoundKind::Visit);
                     quote! {
-                        #idx => #field_name.visit(visitor)
+                        #idx => visit_rs::Visit::visit(#value, visitor)
                     }
                 });
 
@@ -312,12 +567,14 @@ fn derive_visit_variant_fields(
                 let field_idents: Vec<_> = (0..fields.unnamed.len())
                     .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
                     .collect();
-                let field_matches = (0..field_idents.len()).map(|idx| {
-                    let field_ident = &field_idents[idx];
-                    quote! {
-                        #idx => #field_ident.visit(visitor)
-                    }
-                });
+                let field_matches = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                    |(idx, (field_ident, field))| {
+                        let value = with::value(field, quote! { #field_ident }, BoundKind::Visit);
+                        quote! {
+                            #idx => visit_rs::Visit::visit(#value, visitor)
+                        }
+                    },
+                );
 
                 quote! {
                     Self::#variant_name(#(#field_idents),*) => match i {
@@ -337,24 +594,20 @@ fn derive_visit_variant_fields(
     });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFields<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            #(#field_predicates),*
-        {
+        #impl_t {
+            #[allow(unreachable_code)]
             fn visit_variant_fields<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V,
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 let mut i = 0;
-                std::iter::from_fn(move || {
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
                     let res = match self {
                         #(#variant_arms),*
                     };
                     i += 1;
                     Some(res)
-                })
+                }))
             }
         }
     })
@@ -364,20 +617,20 @@ fn derive_visit_variant_fields_covered(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
     // Collect all unique field types for trait bounds with Covered wrapper
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsCovered },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::Visit,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__covered> visit_rs::Covered<'__visit_rs__covered, #ty>: visit_rs::Visit<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::Visit, false, Some(quote! { visit_rs::Covered })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__covered> visit_rs::Covered<'__visit_rs__covered, #ty>: visit_rs::Visit<__visit_rs__V> },
+            ])
+        },
+    )?;
 
     // Generate match arms for each variant
     let variant_arms = data.variants.iter().map(|variant| {
@@ -386,10 +639,11 @@ fn derive_visit_variant_fields_covered(
         match &variant.fields {
             Fields::Named(fields) => {
                 let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_matches = (0..field_names.len()).map(|idx| {
-                    let field_name = &field_names[idx];
+                let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
+                    let field_name = &field.ident;
+                    let value = with::value(field, quote! { #field_name }, BoundKind::Visit);
                     quote! {
-                        #idx => visit_rs::Covered(#field_name).visit(visitor)
+                        #idx => visit_rs::Visit::visit(&visit_rs::Covered(#value), visitor)
                     }
                 });
 
@@ -404,12 +658,14 @@ fn derive_visit_variant_fields_covered(
                 let field_idents: Vec<_> = (0..fields.unnamed.len())
                     .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
                     .collect();
-                let field_matches = (0..field_idents.len()).map(|idx| {
-                    let field_ident = &field_idents[idx];
-                    quote! {
-                        #idx => visit_rs::Covered(#field_ident).visit(visitor)
-                    }
-                });
+                let field_matches = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                    |(idx, (field_ident, field))| {
+                        let value = with::value(field, quote! { #field_ident }, BoundKind::Visit);
+                        quote! {
+                            #idx => visit_rs::Visit::visit(&visit_rs::Covered(#value), visitor)
+                        }
+                    },
+                );
 
                 quote! {
                     Self::#variant_name(#(#field_idents),*) => match i {
@@ -429,24 +685,20 @@ fn derive_visit_variant_fields_covered(
     });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsCovered<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            #(#field_predicates),*
-        {
+        #impl_t {
+            #[allow(unreachable_code)]
             fn visit_variant_fields_covered<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 let mut i = 0;
-                std::iter::from_fn(move || {
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
                     let res = match self {
                         #(#variant_arms),*
                     };
                     i += 1;
                     Some(res)
-                })
+                }))
             }
         }
     })
@@ -456,21 +708,19 @@ fn derive_visit_variant_fields_static(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    // Collect all unique field types for trait bounds
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsStatic },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::Visit,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates
-                    .push(quote! { visit_rs::Static<#ty>: visit_rs::Visit<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::Visit, true, None).unwrap_or_else(|| {
+                vec![parse_quote! { visit_rs::Static<#ty>: visit_rs::Visit<__visit_rs__V> }]
+            })
+        },
+    )?;
 
     // Get rename_all rule for variants
     let rename_all_rule = get_rename_all_attribute(ast);
@@ -481,7 +731,7 @@ fn derive_visit_variant_fields_static(
         .iter()
         .map(|variant| {
             let variant_name_str = get_variant_rename(variant, rename_all_rule);
-            let field_types: Vec<_> = variant.fields.iter().map(|f| &f.ty).collect();
+            let field_types: Vec<_> = variant.fields.iter().collect();
             (variant_name_str, field_types)
         })
         .collect();
@@ -490,10 +740,15 @@ fn derive_visit_variant_fields_static(
     let variant_arms = variant_field_types
         .iter()
         .map(|(variant_name, field_types)| {
-            let field_matches = (0..field_types.len()).map(|idx| {
-                let ty = &field_types[idx];
+            let field_matches = field_types.iter().enumerate().map(|(idx, field)| {
+                let ty = &field.ty;
+                let value = with::static_value(
+                    field,
+                    quote! { &visit_rs::Static::<#ty>::new() },
+                    BoundKind::Visit,
+                );
                 quote! {
-                    #idx => visit_rs::Static::<#ty>::new().visit(visitor)
+                    #idx => visit_rs::Visit::visit(#value, visitor)
                 }
             });
 
@@ -506,18 +761,14 @@ fn derive_visit_variant_fields_static(
         });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsStatic<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            #(#field_predicates),*
-        {
+        #impl_t {
+            #[allow(unreachable_code)]
             fn visit_variant_fields_static<'a>(
                 info: &'a visit_rs::StructInfoData,
                 visitor: &'a mut __visit_rs__V,
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 let mut i = 0;
-                std::iter::from_fn(move || {
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
                     let res = match info.name {
                         #(#variant_arms,)*
                         x => {
@@ -527,7 +778,7 @@ fn derive_visit_variant_fields_static(
                     };
                     i += 1;
                     Some(res)
-                })
+                }))
             }
         }
     })
@@ -537,22 +788,19 @@ fn derive_visit_variant_fields_named(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsNamed },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::Visit,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::Visit<__visit_rs__V> });
-            }
-        }
-    }
-
-    // Get rename_all rule for variants
-    let rename_all_rule = get_rename_all_attribute(ast);
+            with::bound(field, BoundKind::Visit, false, Some(quote! { visit_rs::Named })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::Visit<__visit_rs__V> },
+            ])
+        },
+    )?;
 
     // Extract field metadata for all variants
     let variant_field_metas: Vec<Vec<Vec<TokenStream>>> = data
@@ -567,118 +815,96 @@ fn derive_visit_variant_fields_named(
         })
         .collect();
 
-    let variant_arms = data.variants.iter().enumerate().map(|(variant_idx, variant)| {
-        let variant_name = &variant.ident;
+    let variant_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let variant_name = &variant.ident;
 
-        match &variant.fields {
-            Fields::Named(fields) => {
-                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
-                    let field_name = field.ident.as_ref().unwrap();
-                    let renamed_field = get_field_rename(field, rename_all_rule).unwrap_or_else(|| field_name.to_string());
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                    let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let field_name = field.ident.as_ref().unwrap();
+                        let value = with::value(field, quote! { #field_name }, BoundKind::Visit);
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            #idx => {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                visit_rs::Visit::visit(&named, visitor)
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        #idx => {
-                            let named = visit_rs::Named {
-                                name: Some(#renamed_field),
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: #field_name,
-                            };
-                            named.visit(visitor)
+                        Self::#variant_name { #(#field_names),* } => match i {
+                            #(#field_matches,)*
+                            _ => return None,
                         }
                     }
-                });
-
-                quote! {
-                    Self::#variant_name { #(#field_names),* } => match i {
-                        #(#field_matches,)*
-                        _ => return None,
-                    }
                 }
-            }
-            Fields::Unnamed(fields) => {
-                let field_idents: Vec<_> = (0..fields.unnamed.len())
-                    .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
-                    .collect();
-                let field_matches = (0..field_idents.len()).map(|idx| {
-                    let field_ident = &field_idents[idx];
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
-                        quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                Fields::Unnamed(fields) => {
+                    let field_idents: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                        .collect();
+                    let field_matches = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                        |(idx, (field_ident, field))| {
+                            let value =
+                                with::value(field, quote! { #field_ident }, BoundKind::Visit);
+                            let metadata_ref =
+                                attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                            quote! {
+                                #idx => {
+                                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                        name: None,
+                                        value: #value,
+                                    } metadata: #metadata_ref);
+                                    visit_rs::Visit::visit(&named, visitor)
+                                }
                             }
-                        }
-                    } else {
-                        quote! { &[] }
-                    };
+                        },
+                    );
+
                     quote! {
-                        #idx => {
-                            let named = visit_rs::Named {
-                                name: None,
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: #field_ident,
-                            };
-                            named.visit(visitor)
+                        Self::#variant_name(#(#field_idents),*) => match i {
+                            #(#field_matches,)*
+                            _ => return None,
                         }
                     }
-                });
-
-                quote! {
-                    Self::#variant_name(#(#field_idents),*) => match i {
-                        #(#field_matches,)*
-                        _ => return None,
-                    }
                 }
-            }
-            Fields::Unit => {
-                quote! {
-                    Self::#variant_name => match i {
-                        _ => return None,
+                Fields::Unit => {
+                    quote! {
+                        Self::#variant_name => match i {
+                            _ => return None,
+                        }
                     }
                 }
             }
-        }
-    });
+        });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsNamed<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            #(#field_predicates),*
-        {
+        #impl_t {
+            #[allow(unreachable_code)]
             fn visit_variant_fields_named<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 let mut i = 0;
-                std::iter::from_fn(move || {
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
                     let res = match self {
                         #(#variant_arms),*
                     };
                     i += 1;
                     Some(res)
-                })
+                }))
             }
         }
     })
@@ -688,19 +914,19 @@ fn derive_visit_variant_fields_static_named(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsStaticNamed },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::Visit,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>: visit_rs::Visit<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::Visit, true, Some(quote! { visit_rs::Named })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>: visit_rs::Visit<__visit_rs__V> },
+            ])
+        },
+    )?;
 
     // Get rename_all rule for variants
     let rename_all_rule = get_rename_all_attribute(ast);
@@ -718,110 +944,94 @@ fn derive_visit_variant_fields_static_named(
         })
         .collect();
 
-    let variant_match_arms = data.variants.iter().enumerate().map(|(variant_idx, variant)| {
-        let _variant_name = &variant.ident;
-        let variant_name_str = get_variant_rename(variant, rename_all_rule);
+    let variant_match_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let _variant_name = &variant.ident;
+            let variant_name_str = get_variant_rename(variant, rename_all_rule);
 
-        match &variant.fields {
-            Fields::Named(fields) => {
-                let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
-                    let ty = &field.ty;
-                    let field_name = field.ident.as_ref().unwrap();
-                    let renamed_field = get_field_rename(field, rename_all_rule).unwrap_or_else(|| field_name.to_string());
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let ty = &field.ty;
+                        let value = with::static_value(
+                            field,
+                            quote! { &visit_rs::Static::<#ty>::new() },
+                            BoundKind::Visit,
+                        );
+                        let field_name = field.ident.as_ref().unwrap();
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            #idx => {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                visit_rs::Visit::visit(&named, visitor)
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        #idx => {
-                            let named = visit_rs::Named {
-                                name: Some(#renamed_field),
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: &visit_rs::Static::<#ty>::new(),
-                            };
-                            named.visit(visitor)
+                        #variant_name_str => match i {
+                            #(#field_visits,)*
+                            _ => return None,
                         }
                     }
-                });
-
-                quote! {
-                    #variant_name_str => match i {
-                        #(#field_visits,)*
-                        _ => return None,
-                    }
                 }
-            }
-            Fields::Unnamed(fields) => {
-                let field_visits = fields.unnamed.iter().enumerate().map(|(idx, field)| {
-                    let ty = &field.ty;
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+                Fields::Unnamed(fields) => {
+                    let field_visits = fields.unnamed.iter().enumerate().map(|(idx, field)| {
+                        let ty = &field.ty;
+                        let value = with::static_value(
+                            field,
+                            quote! { &visit_rs::Static::<#ty>::new() },
+                            BoundKind::Visit,
+                        );
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            #idx => {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: None,
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                visit_rs::Visit::visit(&named, visitor)
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        #idx => {
-                            let named = visit_rs::Named {
-                                name: None,
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: &visit_rs::Static::<#ty>::new(),
-                            };
-                            named.visit(visitor)
+                        #variant_name_str => match i {
+                            #(#field_visits,)*
+                            _ => return None,
                         }
                     }
-                });
-
-                quote! {
-                    #variant_name_str => match i {
-                        #(#field_visits,)*
-                        _ => return None,
-                    }
                 }
-            }
-            Fields::Unit => {
-                quote! {
-                    #variant_name_str => match i {
-                        _ => return None,
+                Fields::Unit => {
+                    quote! {
+                        #variant_name_str => match i {
+                            _ => return None,
+                        }
                     }
                 }
             }
-        }
-    });
+        });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsStaticNamed<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor,
-            #(#field_predicates),*
-        {
+        #impl_t {
+            #[allow(unreachable_code)]
             fn visit_variant_fields_static_named<'a>(
                 info: &'a visit_rs::StructInfoData,
                 visitor: &'a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 let mut i = 0;
-                std::iter::from_fn(move || {
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
                     let res = match info.name {
                         #(#variant_match_arms,)*
                         x => {
@@ -831,7 +1041,7 @@ fn derive_visit_variant_fields_static_named(
                     };
                     i += 1;
                     Some(res)
-                })
+                }))
             }
         }
     })
@@ -841,19 +1051,26 @@ fn derive_visit_variant_fields_async(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsAsync },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor + Send },
+            parse_quote! { <__visit_rs__V as visit_rs::Visitor>::Result: Send },
+        ],
+        BoundKind::VisitAsync,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { #ty: visit_rs::VisitAsync<__visit_rs__V> });
-            }
-        }
-    }
+            let mut inferred = vec![parse_quote! { for<'__visit_rs__sync> #ty: Sync }];
+            inferred.extend(
+                with::bound(field, BoundKind::VisitAsync, false, None).unwrap_or_else(|| {
+                    vec![parse_quote! { #ty: visit_rs::VisitAsync<__visit_rs__V> }]
+                }),
+            );
+            inferred
+        },
+    )?;
 
     let variant_arms = data.variants.iter().map(|variant| {
         let variant_name = &variant.ident;
@@ -861,8 +1078,10 @@ fn derive_visit_variant_fields_async(
         match &variant.fields {
             Fields::Named(fields) => {
                 let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_visits = field_names.iter().map(|field_name| {
-                    quote! { yield visit_rs::VisitAsync::visit_async(#field_name, visitor).await; }
+                let field_visits = fields.named.iter().map(|field| {
+                    let field_name = &field.ident;
+                    let value = with::value(field, quote! { #field_name }, BoundKind::VisitAsync);
+                    yield_visit(quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await })
                 });
 
                 quote! {
@@ -875,9 +1094,17 @@ fn derive_visit_variant_fields_async(
                 let field_idents: Vec<_> = (0..fields.unnamed.len())
                     .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
                     .collect();
-                let field_visits = field_idents.iter().map(|field_ident| {
-                    quote! { yield visit_rs::VisitAsync::visit_async(#field_ident, visitor).await; }
-                });
+                let field_visits =
+                    field_idents
+                        .iter()
+                        .zip(&fields.unnamed)
+                        .map(|(ident, field)| {
+                            let value =
+                                with::value(field, quote! { #ident }, BoundKind::VisitAsync);
+                            yield_visit(
+                                quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await },
+                            )
+                        });
 
                 quote! {
                     Self::#variant_name(#(#field_idents),*) => {
@@ -894,13 +1121,7 @@ fn derive_visit_variant_fields_async(
     });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsAsync<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor + Send,
-            <__visit_rs__V as visit_rs::Visitor>::Result: Send,
-            #(#field_predicates),*
-        {
+        #impl_t {
             fn visit_variant_fields_async<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V
@@ -919,23 +1140,113 @@ fn derive_visit_variant_fields_async(
     })
 }
 
-fn derive_visit_variant_fields_covered_async(
+fn derive_visit_variant_fields_async_local(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsAsyncLocal },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::VisitAsyncLocal,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__covered> visit_rs::Covered<'__visit_rs__covered, #ty>: visit_rs::VisitAsync<__visit_rs__V> });
+            with::bound(field, BoundKind::VisitAsyncLocal, false, None).unwrap_or_else(|| {
+                vec![parse_quote! { #ty: visit_rs::VisitAsyncLocal<__visit_rs__V> }]
+            })
+        },
+    )?;
+
+    let variant_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+
+        match &variant.fields {
+            Fields::Named(fields) => {
+                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let field_visits = fields.named.iter().map(|field| {
+                    let field_name = &field.ident;
+                    let value =
+                        with::value(field, quote! { #field_name }, BoundKind::VisitAsyncLocal);
+                    yield_visit(
+                        quote! { visit_rs::VisitAsyncLocal::visit_async_local(#value, visitor).await },
+                    )
+                });
+
+                quote! {
+                    Self::#variant_name { #(#field_names),* } => {
+                        #(#field_visits)*
+                    }
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let field_idents: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                    .collect();
+                let field_visits = field_idents.iter().zip(&fields.unnamed).map(|(field_ident, field)| {
+                    let value =
+                        with::value(field, quote! { #field_ident }, BoundKind::VisitAsyncLocal);
+                    yield_visit(
+                        quote! { visit_rs::VisitAsyncLocal::visit_async_local(#value, visitor).await },
+                    )
+                });
+
+                quote! {
+                    Self::#variant_name(#(#field_idents),*) => {
+                        #(#field_visits)*
+                    }
+                }
+            }
+            Fields::Unit => {
+                quote! {
+                    Self::#variant_name => {}
+                }
             }
         }
-    }
+    });
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_variant_fields_async_local<'a>(
+                &'a self,
+                visitor: &'a mut __visit_rs__V
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
+                visit_rs::lib::async_stream::stream! {
+                    match self {
+                        #(#variant_arms)*
+                    }
+                    #[allow(unreachable_code)]
+                    if false {
+                        yield unreachable!() as <__visit_rs__V as visit_rs::Visitor>::Result
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_covered_async(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsCoveredAsync },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor + Send },
+            parse_quote! { <__visit_rs__V as visit_rs::Visitor>::Result: Send },
+        ],
+        BoundKind::VisitAsync,
+        |field| {
+            let ty = &field.ty;
+            let mut inferred = vec![parse_quote! { for<'__visit_rs__sync> #ty: Sync }];
+            inferred.extend(with::bound(field, BoundKind::VisitAsync, false, Some(quote! { visit_rs::Covered })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__covered> visit_rs::Covered<'__visit_rs__covered, #ty>: visit_rs::VisitAsync<__visit_rs__V> },
+            ]));
+            inferred
+        },
+    )?;
 
     let variant_arms = data.variants.iter().map(|variant| {
         let variant_name = &variant.ident;
@@ -943,8 +1254,9 @@ fn derive_visit_variant_fields_covered_async(
         match &variant.fields {
             Fields::Named(fields) => {
                 let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_visits = field_names.iter().map(|field_name| {
-                    quote! { yield visit_rs::VisitAsync::visit_async(&visit_rs::Covered(#field_name), visitor).await; }
+                let field_visits = field_names.iter().zip(&fields.named).map(|(field_name, field)| {
+                    let value = with::value(field, quote! { #field_name }, BoundKind::VisitAsync);
+                    yield_visit(quote! { visit_rs::VisitAsync::visit_async(&visit_rs::Covered(#value), visitor).await })
                 });
 
                 quote! {
@@ -957,8 +1269,9 @@ fn derive_visit_variant_fields_covered_async(
                 let field_idents: Vec<_> = (0..fields.unnamed.len())
                     .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
                     .collect();
-                let field_visits = field_idents.iter().map(|field_ident| {
-                    quote! { yield visit_rs::VisitAsync::visit_async(&visit_rs::Covered(#field_ident), visitor).await; }
+                let field_visits = field_idents.iter().zip(&fields.unnamed).map(|(field_ident, field)| {
+                    let value = with::value(field, quote! { #field_ident }, BoundKind::VisitAsync);
+                    yield_visit(quote! { visit_rs::VisitAsync::visit_async(&visit_rs::Covered(#value), visitor).await })
                 });
 
                 quote! {
@@ -976,13 +1289,7 @@ fn derive_visit_variant_fields_covered_async(
     });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsCoveredAsync<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor + Send,
-            <__visit_rs__V as visit_rs::Visitor>::Result: Send,
-            #(#field_predicates),*
-        {
+        #impl_t {
             fn visit_variant_fields_covered_async<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V
@@ -1005,20 +1312,22 @@ fn derive_visit_variant_fields_static_async(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsStaticAsync },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor + Send },
+            parse_quote! { <__visit_rs__V as visit_rs::Visitor>::Result: Send },
+        ],
+        BoundKind::VisitAsync,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates
-                    .push(quote! { visit_rs::Static<#ty>: visit_rs::VisitAsync<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::VisitAsync, true, None).unwrap_or_else(|| {
+                vec![parse_quote! { visit_rs::Static<#ty>: visit_rs::VisitAsync<__visit_rs__V> }]
+            })
+        },
+    )?;
 
     // Get rename_all rule for variants
     let rename_all_rule = get_rename_all_attribute(ast);
@@ -1031,7 +1340,12 @@ fn derive_visit_variant_fields_static_async(
             Fields::Named(fields) => {
                 let field_visits = fields.named.iter().map(|field| {
                     let ty = &field.ty;
-                    quote! { yield visit_rs::VisitAsync::visit_async(&visit_rs::Static::<#ty>::new(), visitor).await; }
+                    let value = with::static_value(
+                        field,
+                        quote! { &visit_rs::Static::<#ty>::new() },
+                        BoundKind::VisitAsync,
+                    );
+                    yield_visit(quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await })
                 });
 
                 quote! {
@@ -1043,7 +1357,12 @@ fn derive_visit_variant_fields_static_async(
             Fields::Unnamed(fields) => {
                 let field_visits = fields.unnamed.iter().map(|field| {
                     let ty = &field.ty;
-                    quote! { yield visit_rs::VisitAsync::visit_async(&visit_rs::Static::<#ty>::new(), visitor).await; }
+                    let value = with::static_value(
+                        field,
+                        quote! { &visit_rs::Static::<#ty>::new() },
+                        BoundKind::VisitAsync,
+                    );
+                    yield_visit(quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await })
                 });
 
                 quote! {
@@ -1061,13 +1380,7 @@ fn derive_visit_variant_fields_static_async(
     });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsStaticAsync<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor + Send,
-            <__visit_rs__V as visit_rs::Visitor>::Result: Send,
-            #(#field_predicates),*
-        {
+        #impl_t {
             fn visit_variant_fields_static_async<'a>(
                 info: &'a visit_rs::StructInfoData,
                 visitor: &'a mut __visit_rs__V
@@ -1093,22 +1406,27 @@ fn derive_visit_variant_fields_named_async(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsNamedAsync },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor + Send },
+            parse_quote! { <__visit_rs__V as visit_rs::Visitor>::Result: Send },
+        ],
+        BoundKind::VisitAsync,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::VisitAsync<__visit_rs__V> });
-            }
-        }
-    }
+            let mut inferred = vec![parse_quote! { for<'__visit_rs__sync> #ty: Sync }];
+            inferred.extend(with::bound(field, BoundKind::VisitAsync, false, Some(quote! { visit_rs::Named })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::VisitAsync<__visit_rs__V> },
+            ]));
+            inferred
+        },
+    )?;
 
-    // Get rename_all rule for variants
-    let rename_all_rule = get_rename_all_attribute(ast);
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await });
 
     // Extract field metadata for all variants
     let variant_field_metas: Vec<Vec<Vec<TokenStream>>> = data
@@ -1123,106 +1441,213 @@ fn derive_visit_variant_fields_named_async(
         })
         .collect();
 
-    let variant_arms = data.variants.iter().enumerate().map(|(variant_idx, variant)| {
-        let variant_name = &variant.ident;
+    let variant_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let variant_name = &variant.ident;
 
-        match &variant.fields {
-            Fields::Named(fields) => {
-                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
-                let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
-                    let field_name = field.ident.as_ref().unwrap();
-                    let renamed_field = get_field_rename(field, rename_all_rule).unwrap_or_else(|| field_name.to_string());
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                    let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let field_name = field.ident.as_ref().unwrap();
+                        let value =
+                            with::value(field, quote! { #field_name }, BoundKind::VisitAsync);
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                #yield_named
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        {
-                            let named = visit_rs::Named {
-                                name: Some(#renamed_field),
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: #field_name,
-                            };
-                            yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                        Self::#variant_name { #(#field_names),* } => {
+                            #(#field_visits)*
                         }
                     }
-                });
+                }
+                Fields::Unnamed(fields) => {
+                    let field_idents: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                        .collect();
+                    let field_visits = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                        |(idx, (field_ident, field))| {
+                            let value =
+                                with::value(field, quote! { #field_ident }, BoundKind::VisitAsync);
+                            let metadata_ref =
+                                attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                            quote! {
+                                {
+                                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                        name: None,
+                                        value: #value,
+                                    } metadata: #metadata_ref);
+                                    #yield_named
+                                }
+                            }
+                        },
+                    );
 
-                quote! {
-                    Self::#variant_name { #(#field_names),* } => {
-                        #(#field_visits)*
+                    quote! {
+                        Self::#variant_name(#(#field_idents),*) => {
+                            #(#field_visits)*
+                        }
+                    }
+                }
+                Fields::Unit => {
+                    quote! {
+                        Self::#variant_name => {}
                     }
                 }
             }
-            Fields::Unnamed(fields) => {
-                let field_idents: Vec<_> = (0..fields.unnamed.len())
-                    .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
-                    .collect();
-                let field_visits = field_idents.iter().enumerate().map(|(idx, field_ident)| {
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+        });
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_variant_fields_named_async<'a>(
+                &'a self,
+                visitor: &'a mut __visit_rs__V
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + Send + 'a {
+                visit_rs::lib::async_stream::stream! {
+                    match self {
+                        #(#variant_arms)*
+                    }
+                    #[allow(unreachable_code)]
+                    if false {
+                        yield unreachable!() as <__visit_rs__V as visit_rs::Visitor>::Result
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_named_async_local(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsNamedAsyncLocal },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::VisitAsyncLocal,
+        |field| {
+            let ty = &field.ty;
+            with::bound(field, BoundKind::VisitAsyncLocal, false, Some(quote! { visit_rs::Named })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::VisitAsyncLocal<__visit_rs__V> },
+            ])
+        },
+    )?;
+
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsyncLocal::visit_async_local(&named, visitor).await });
+
+    // Extract field metadata for all variants
+    let variant_field_metas: Vec<Vec<Vec<TokenStream>>> = data
+        .variants
+        .iter()
+        .map(|variant| {
+            variant
+                .fields
+                .iter()
+                .map(|field| attrs::extract_all_meta(&field.attrs))
+                .collect()
+        })
+        .collect();
+
+    let variant_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let variant_name = &variant.ident;
+
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                    let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let field_name = field.ident.as_ref().unwrap();
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                        let value =
+                            with::value(field, quote! { #field_name }, BoundKind::VisitAsyncLocal);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                #yield_named
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        {
-                            let named = visit_rs::Named {
-                                name: None,
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: #field_ident,
-                            };
-                            yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                        Self::#variant_name { #(#field_names),* } => {
+                            #(#field_visits)*
                         }
                     }
-                });
+                }
+                Fields::Unnamed(fields) => {
+                    let field_idents: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                        .collect();
+                    let field_visits = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                        |(idx, (field_ident, field))| {
+                            let metadata_ref =
+                                attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                            let value = with::value(
+                                field,
+                                quote! { #field_ident },
+                                BoundKind::VisitAsyncLocal,
+                            );
+                            quote! {
+                                {
+                                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                        name: None,
+                                        value: #value,
+                                    } metadata: #metadata_ref);
+                                    #yield_named
+                                }
+                            }
+                        },
+                    );
 
-                quote! {
-                    Self::#variant_name(#(#field_idents),*) => {
-                        #(#field_visits)*
+                    quote! {
+                        Self::#variant_name(#(#field_idents),*) => {
+                            #(#field_visits)*
+                        }
                     }
                 }
-            }
-            Fields::Unit => {
-                quote! {
-                    Self::#variant_name => {}
+                Fields::Unit => {
+                    quote! {
+                        Self::#variant_name => {}
+                    }
                 }
             }
-        }
-    });
+        });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsNamedAsync<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor + Send,
-            <__visit_rs__V as visit_rs::Visitor>::Result: Send,
-            #(#field_predicates),*
-        {
-            fn visit_variant_fields_named_async<'a>(
+        #impl_t {
+            fn visit_variant_fields_named_async_local<'a>(
                 &'a self,
                 visitor: &'a mut __visit_rs__V
-            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + Send + 'a {
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
                 visit_rs::lib::async_stream::stream! {
                     match self {
                         #(#variant_arms)*
@@ -1241,23 +1666,29 @@ fn derive_visit_variant_fields_static_named_async(
     ast: &DeriveInput,
     data: &DataEnum,
 ) -> Result<TokenStream, syn::Error> {
-    let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-
-    let mut ty_set = HashSet::new();
-    let mut field_predicates = Vec::new();
-    for variant in &data.variants {
-        for field in &variant.fields {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsStaticNamedAsync },
+        vec![
+            parse_quote! { __visit_rs__V: visit_rs::Visitor + Send },
+            parse_quote! { <__visit_rs__V as visit_rs::Visitor>::Result: Send },
+        ],
+        BoundKind::VisitAsync,
+        |field| {
             let ty = &field.ty;
-            if ty_set.insert(ty) {
-                field_predicates.push(quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>: visit_rs::VisitAsync<__visit_rs__V> });
-            }
-        }
-    }
+            with::bound(field, BoundKind::VisitAsync, true, Some(quote! { visit_rs::Named })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>: visit_rs::VisitAsync<__visit_rs__V> },
+            ])
+        },
+    )?;
 
     // Get rename_all rule for variants
     let rename_all_rule = get_rename_all_attribute(ast);
 
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await });
+
     // Extract field metadata for all variants
     let variant_field_metas: Vec<Vec<Vec<TokenStream>>> = data
         .variants
@@ -1271,101 +1702,83 @@ fn derive_visit_variant_fields_static_named_async(
         })
         .collect();
 
-    let variant_match_arms = data.variants.iter().enumerate().map(|(variant_idx, variant)| {
-        let _variant_name = &variant.ident;
-        let variant_name_str = get_variant_rename(variant, rename_all_rule);
+    let variant_match_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let _variant_name = &variant.ident;
+            let variant_name_str = get_variant_rename(variant, rename_all_rule);
 
-        match &variant.fields {
-            Fields::Named(fields) => {
-                let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
-                    let ty = &field.ty;
-                    let field_name = field.ident.as_ref().unwrap();
-                    let renamed_field = get_field_rename(field, rename_all_rule).unwrap_or_else(|| field_name.to_string());
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_visits = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let ty = &field.ty;
+                        let value = with::static_value(
+                            field,
+                            quote! { &visit_rs::Static::<#ty>::new() },
+                            BoundKind::VisitAsync,
+                        );
+                        let field_name = field.ident.as_ref().unwrap();
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                #yield_named
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        {
-                            let named = visit_rs::Named {
-                                name: Some(#renamed_field),
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: &visit_rs::Static::<#ty>::new(),
-                            };
-                            yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                        #variant_name_str => {
+                            #(#field_visits)*
                         }
                     }
-                });
-
-                quote! {
-                    #variant_name_str => {
-                        #(#field_visits)*
-                    }
                 }
-            }
-            Fields::Unnamed(fields) => {
-                let field_visits = fields.unnamed.iter().enumerate().map(|(idx, field)| {
-                    let ty = &field.ty;
-                    let metadata_ref = if !variant_field_metas[variant_idx][idx].is_empty() {
-                        let metas = &variant_field_metas[variant_idx][idx];
-                        let count = metas.len();
+                Fields::Unnamed(fields) => {
+                    let field_visits = fields.unnamed.iter().enumerate().map(|(idx, field)| {
+                        let ty = &field.ty;
+                        let value = with::static_value(
+                            field,
+                            quote! { &visit_rs::Static::<#ty>::new() },
+                            BoundKind::VisitAsync,
+                        );
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
                         quote! {
-                            if cfg!(feature = "meta") {
-                                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                                &META
-                            } else {
-                                &[]
+                            {
+                                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                    name: None,
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                #yield_named
                             }
                         }
-                    } else {
-                        quote! { &[] }
-                    };
+                    });
+
                     quote! {
-                        {
-                            let named = visit_rs::Named {
-                                name: None,
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: &visit_rs::Static::<#ty>::new(),
-                            };
-                            yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                        #variant_name_str => {
+                            #(#field_visits)*
                         }
                     }
-                });
-
-                quote! {
-                    #variant_name_str => {
-                        #(#field_visits)*
-                    }
                 }
-            }
-            Fields::Unit => {
-                quote! {
-                    #variant_name_str => {}
+                Fields::Unit => {
+                    quote! {
+                        #variant_name_str => {}
+                    }
                 }
             }
-        }
-    });
+        });
 
     Ok(quote! {
-        impl<__visit_rs__V, #impl_generics> visit_rs::VisitVariantFieldsStaticNamedAsync<__visit_rs__V> for #ident #ty_generics
-        #where_clause
-        where
-            __visit_rs__V: visit_rs::Visitor + Send,
-            <__visit_rs__V as visit_rs::Visitor>::Result: Send,
-            #(#field_predicates),*
-        {
+        #impl_t {
             fn visit_variant_fields_static_named_async<'a>(
                 info: &'a visit_rs::StructInfoData,
                 visitor: &'a mut __visit_rs__V
@@ -1386,3 +1799,689 @@ fn derive_visit_variant_fields_static_named_async(
         }
     })
 }
+
+fn derive_visit_variant_fields_mut(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsMut },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::VisitMut,
+        |field| {
+            let ty = &field.ty;
+            with::bound(field, BoundKind::VisitMut, false, None)
+                .unwrap_or_else(|| vec![parse_quote! { #ty: visit_rs::VisitMut<__visit_rs__V> }])
+        },
+    )?;
+
+    // Generate match arms for each variant
+    let variant_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+
+        match &variant.fields {
+            Fields::Named(fields) => {
+                let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
+                    let field_name = &field.ident;
+                    let value = with::value(field, quote! { #field_name }, BoundKind::VisitMut);
+                    quote! {
+                        #idx => visit_rs::VisitMut::visit_mut(#value, visitor)
+                    }
+                });
+
+                quote! {
+                    Self::#variant_name { #(#field_names),* } => match i {
+                        #(#field_matches,)*
+                        _ => return None,
+                    }
+                }
+            }
+            Fields::Unnamed(fields) => {
+                let field_idents: Vec<_> = (0..fields.unnamed.len())
+                    .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                    .collect();
+                let field_matches = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                    |(idx, (field_ident, field))| {
+                        let value =
+                            with::value(field, quote! { #field_ident }, BoundKind::VisitMut);
+                        quote! {
+                            #idx => visit_rs::VisitMut::visit_mut(#value, visitor)
+                        }
+                    },
+                );
+
+                quote! {
+                    Self::#variant_name(#(#field_idents),*) => match i {
+                        #(#field_matches,)*
+                        _ => return None,
+                    }
+                }
+            }
+            Fields::Unit => {
+                quote! {
+                    Self::#variant_name => match i {
+                        _ => return None,
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #impl_t {
+            #[allow(unreachable_code)]
+            fn visit_variant_fields_mut<'a>(
+                &'a mut self,
+                visitor: &'a mut __visit_rs__V,
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
+                let mut i = 0;
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
+                    let res = match self {
+                        #(#variant_arms),*
+                    };
+                    i += 1;
+                    Some(res)
+                }))
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_named_mut(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsNamedMut },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::VisitMut,
+        |field| {
+            let ty = &field.ty;
+            with::bound(field, BoundKind::VisitMut, false, Some(quote! { visit_rs::NamedMut })).unwrap_or_else(|| vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::NamedMut<'__visit_rs__named, #ty>: visit_rs::VisitMut<__visit_rs__V> },
+            ])
+        },
+    )?;
+
+    // Extract field metadata for all variants
+    let variant_field_metas: Vec<Vec<Vec<TokenStream>>> = data
+        .variants
+        .iter()
+        .map(|variant| {
+            variant
+                .fields
+                .iter()
+                .map(|field| attrs::extract_all_meta(&field.attrs))
+                .collect()
+        })
+        .collect();
+
+    let variant_arms = data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, variant)| {
+            let variant_name = &variant.ident;
+
+            match &variant.fields {
+                Fields::Named(fields) => {
+                    let field_names: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                    let field_matches = fields.named.iter().enumerate().map(|(idx, field)| {
+                        let field_name = field.ident.as_ref().unwrap();
+                        let renamed_field =
+                            get_field_rename(field, get_variant_fields_rules(ast, variant))
+                                .unwrap_or_else(|| field_name.to_string());
+                        let metadata_ref =
+                            attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                        let value = with::value(field, quote! { #field_name }, BoundKind::VisitMut);
+                        quote! {
+                            #idx => {
+                                let mut named = visit_rs::__with_metadata!(visit_rs::NamedMut {
+                                    name: Some(#renamed_field),
+                                    value: #value,
+                                } metadata: #metadata_ref);
+                                visit_rs::VisitMut::visit_mut(&mut named, visitor)
+                            }
+                        }
+                    });
+
+                    quote! {
+                        Self::#variant_name { #(#field_names),* } => match i {
+                            #(#field_matches,)*
+                            _ => return None,
+                        }
+                    }
+                }
+                Fields::Unnamed(fields) => {
+                    let field_idents: Vec<_> = (0..fields.unnamed.len())
+                        .map(|i| Ident::new(&format!("tup_{}", i), Span::call_site()))
+                        .collect();
+                    let field_matches = field_idents.iter().zip(&fields.unnamed).enumerate().map(
+                        |(idx, (field_ident, field))| {
+                            let metadata_ref =
+                                attrs::metadata_ref(&variant_field_metas[variant_idx][idx]);
+                            let value =
+                                with::value(field, quote! { #field_ident }, BoundKind::VisitMut);
+                            quote! {
+                                #idx => {
+                                    let mut named = visit_rs::__with_metadata!(visit_rs::NamedMut {
+                                        name: None,
+                                        value: #value,
+                                    } metadata: #metadata_ref);
+                                    visit_rs::VisitMut::visit_mut(&mut named, visitor)
+                                }
+                            }
+                        },
+                    );
+
+                    quote! {
+                        Self::#variant_name(#(#field_idents),*) => match i {
+                            #(#field_matches,)*
+                            _ => return None,
+                        }
+                    }
+                }
+                Fields::Unit => {
+                    quote! {
+                        Self::#variant_name => match i {
+                            _ => return None,
+                        }
+                    }
+                }
+            }
+        });
+
+    Ok(quote! {
+        #impl_t {
+            #[allow(unreachable_code)]
+            fn visit_variant_fields_named_mut<'a>(
+                &'a mut self,
+                visitor: &'a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
+                let mut i = 0;
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
+                    let res = match self {
+                        #(#variant_arms),*
+                    };
+                    i += 1;
+                    Some(res)
+                }))
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_owned(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    // As for structs, moving fields out of a `Drop` type does not compile
+    if !has_visit_flag(&ast.attrs, "owned") {
+        return Ok(TokenStream::new());
+    }
+
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::VisitVariantFieldsOwned },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Visitor }],
+        BoundKind::VisitOwned,
+        |field| {
+            let ty = &field.ty;
+            with::bound(field, BoundKind::VisitOwned, false, None)
+                .unwrap_or_else(|| vec![parse_quote! { #ty: visit_rs::VisitOwned<__visit_rs__V> }])
+        },
+    )?;
+
+    // Every field of every variant gets its own `Option` slot, so the iterator can hand out
+    // fields one at a time without knowing which variant it was built from
+    let mut slot_decls = Vec::new();
+    let mut slot_arms = Vec::new();
+    let variant_arms = data.variants.iter().enumerate().map(|(variant_idx, variant)| {
+        let variant_name = &variant.ident;
+        let mut bindings = Vec::new();
+        let mut assigns = Vec::new();
+        for (idx, field) in variant.fields.iter().enumerate() {
+            let ty = &field.ty;
+            let member = match &field.ident {
+                Some(name) => quote! { #name },
+                None => {
+                    let index = syn::Index::from(idx);
+                    quote! { #index }
+                }
+            };
+            let value = Ident::new(&format!("__visit_rs__value{}", idx), Span::call_site());
+            let slot = Ident::new(
+                &format!("__visit_rs__f{}_{}", variant_idx, idx),
+                Span::call_site(),
+            );
+            bindings.push(quote! { #member: #value });
+            assigns.push(quote! { #slot = Some(#value); });
+            slot_decls.push(quote! { let mut #slot: Option<#ty> = None; });
+            let visited = with::value(field, quote! { value }, BoundKind::VisitOwned);
+            slot_arms.push(quote! {
+                (#variant_idx, #idx) => #slot.take().map(|value| visit_rs::VisitOwned::visit_owned(#visited, visitor))
+            });
+        }
+
+        quote! {
+            Self::#variant_name { #(#bindings,)* .. } => {
+                #(#assigns)*
+                #variant_idx
+            }
+        }
+    });
+    let variant_arms: Vec<_> = variant_arms.collect();
+
+    Ok(quote! {
+        #impl_t {
+            #[allow(unreachable_code, unused_assignments)]
+            fn visit_variant_fields_owned<'__visit_rs__a>(
+                self,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
+            where
+                Self: '__visit_rs__a,
+            {
+                #(#slot_decls)*
+                let variant: usize = match self {
+                    #(#variant_arms)*
+                };
+                let mut i = 0;
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
+                    let res = match (variant, i) {
+                        #(#slot_arms,)*
+                        _ => None,
+                    };
+                    i += 1;
+                    res
+                }))
+            }
+        }
+    })
+}
+
+fn derive_from_variant_fields(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_variant_impl(
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        quote! { visit_rs::FromVariantFields },
+        vec![parse_quote! { __visit_rs__V: visit_rs::Source }],
+        BoundKind::Build,
+        |field| {
+            let ty = &field.ty;
+            vec![
+                parse_quote! { for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>: visit_rs::Build<__visit_rs__V, Output = #ty> },
+            ]
+        },
+    )?;
+
+    // Get rename_all rule for variants
+    let rename_all_rule = get_rename_all_attribute(ast);
+
+    let variant_arms = data.variants.iter().enumerate().map(|(index, variant)| {
+        let variant_name = &variant.ident;
+        let variant_name_str = get_variant_rename(variant, rename_all_rule);
+
+        let built_fields = variant.fields.iter().enumerate().map(|(idx, field)| {
+            let ty = &field.ty;
+            let (member, name) = match &field.ident {
+                Some(field_name) => {
+                    let renamed_field =
+                        get_field_rename(field, get_variant_fields_rules(ast, variant))
+                            .unwrap_or_else(|| field_name.to_string());
+                    (quote! { #field_name }, quote! { Some(#renamed_field) })
+                }
+                None => {
+                    let index = syn::Index::from(idx);
+                    (quote! { #index }, quote! { None })
+                }
+            };
+            let metas = attrs::extract_all_meta(&field.attrs);
+            let metadata_ref = attrs::metadata_ref(&metas);
+            quote! {
+                #member: {
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: &visit_rs::Static::<#ty>::new(),
+                    } metadata: #metadata_ref);
+                    visit_rs::Build::build(&named, source)?
+                }
+            }
+        });
+
+        quote! {
+            (Some(#index), #variant_name_str) => Some((|| -> Result<Self, <__visit_rs__V as visit_rs::Source>::Error> {
+                Ok(Self::#variant_name { #(#built_fields,)* })
+            })())
+        }
+    });
+
+    Ok(quote! {
+        #impl_t {
+            fn from_variant_fields(
+                info: &visit_rs::StructInfoData,
+                source: &mut __visit_rs__V,
+            ) -> Option<Result<Self, <__visit_rs__V as visit_rs::Source>::Error>> {
+                match (info.variant.map(|variant| variant.index), info.name) {
+                    #(#variant_arms,)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+fn derive_dyn_visit_variant_fields(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    // Bounds are higher-ranked so that enums with non-`DynVisit` fields still compile
+    let mut generics = ast.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    push_field_bounds(
+        predicates,
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        BoundKind::DynVisit,
+        |field| {
+            let ty = &field.ty;
+            vec![parse_quote! { for<'__visit_rs__dyn> #ty: visit_rs::dynamic::DynVisit }]
+        },
+    )?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let variant_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+
+        let members: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| match &field.ident {
+                Some(field_name) => quote! { #field_name },
+                None => {
+                    let index = syn::Index::from(idx);
+                    quote! { #index }
+                }
+            })
+            .collect();
+        let values: Vec<_> = (0..members.len())
+            .map(|idx| Ident::new(&format!("__visit_rs__value{idx}"), Span::call_site()))
+            .collect();
+
+        let visit_fields = variant.fields.iter().zip(&values).map(|(field, value)| {
+            let name = match &field.ident {
+                Some(field_name) => {
+                    let renamed_field =
+                        get_field_rename(field, get_variant_fields_rules(ast, variant))
+                            .unwrap_or_else(|| field_name.to_string());
+                    quote! { Some(#renamed_field) }
+                }
+                None => quote! { None },
+            };
+            let metas = attrs::extract_all_meta(&field.attrs);
+            let metadata_ref = attrs::metadata_ref(&metas);
+            quote! {
+                visitor.visit_field(visit_rs::__with_metadata!(visit_rs::Named {
+                    name: #name,
+                    value: &#value,
+                } metadata: #metadata_ref));
+            }
+        });
+
+        quote! {
+            Self::#variant_name { #(#members: #values,)* } => {
+                #(#visit_fields)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::dynamic::DynVisitFields for #ident #ty_generics #where_clause {
+            #[allow(unreachable_code)]
+            fn dyn_visit_fields(&self, visitor: &mut dyn visit_rs::dynamic::DynVisitor) {
+                match self {
+                    #(#variant_arms)*
+                }
+            }
+        }
+
+        impl #impl_generics visit_rs::dynamic::DynVisit for #ident #ty_generics #where_clause {
+            fn dyn_visit(&self, visitor: &mut dyn visit_rs::dynamic::DynVisitor) {
+                visitor.visit_variant(
+                    &<Self as visit_rs::EnumInfo>::DATA,
+                    visit_rs::Variant {
+                        info: visit_rs::EnumInfo::variant_info(self),
+                        value: self,
+                    },
+                );
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_pathed(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(syn::parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::path::PathVisitor });
+    push_field_bounds(
+        predicates,
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        BoundKind::Visit,
+        |field| {
+            let ty = &field.ty;
+            with::bound(field, BoundKind::Visit, false, Some(quote! { visit_rs::Named })).unwrap_or_else(|| {
+                vec![parse_quote! {
+                    for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::Visit<__visit_rs__V>
+                }]
+            })
+        },
+    )?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Get rename_all rule for variants
+    let rename_all_rule = get_rename_all_attribute(ast);
+
+    let variant_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let variant_name_str = get_variant_rename(variant, rename_all_rule);
+
+        let members: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| match &field.ident {
+                Some(field_name) => quote! { #field_name },
+                None => {
+                    let index = syn::Index::from(idx);
+                    quote! { #index }
+                }
+            })
+            .collect();
+        let values: Vec<_> = (0..members.len())
+            .map(|idx| Ident::new(&format!("__visit_rs__value{idx}"), Span::call_site()))
+            .collect();
+
+        let field_matches = variant.fields.iter().zip(&values).enumerate().map(
+            |(idx, (field, value))| {
+                let value = with::value(field, quote! { #value }, BoundKind::Visit);
+                let (name, segment) =
+                    match get_field_rename(field, get_variant_fields_rules(ast, variant)) {
+                        Some(renamed) => (
+                            quote! { Some(#renamed) },
+                            quote! { visit_rs::path::PathSegment::Field(#renamed) },
+                        ),
+                        None => (
+                            quote! { None },
+                            quote! { visit_rs::path::PathSegment::TupleIndex(#idx) },
+                        ),
+                    };
+                let metas = attrs::extract_all_meta(&field.attrs);
+                let metadata_ref = attrs::metadata_ref(&metas);
+                quote! {
+                    #idx => {
+                        let named = visit_rs::__with_metadata!(visit_rs::Named {
+                            name: #name,
+                            value: #value,
+                        } metadata: #metadata_ref);
+                        visit_rs::path::PathVisitor::path_mut(visitor)
+                            .push(visit_rs::path::PathSegment::Variant(#variant_name_str));
+                        let res = visit_rs::path::PathVisitor::visit_at(visitor, #segment, &named);
+                        visit_rs::path::PathVisitor::path_mut(visitor).pop();
+                        res
+                    }
+                }
+            },
+        );
+
+        quote! {
+            Self::#variant_name { #(#members: #values,)* } => match i {
+                #(#field_matches,)*
+                _ => return None,
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::path::VisitVariantFieldsPathed<__visit_rs__V> for #ident #ty_generics #where_clause {
+            #[allow(unreachable_code)]
+            fn visit_variant_fields_pathed<'a>(
+                &'a self,
+                visitor: &'a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + 'a {
+                let mut i = 0;
+                visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn(move || {
+                    let res = match self {
+                        #(#variant_arms),*
+                    };
+                    i += 1;
+                    Some(res)
+                }))
+            }
+        }
+    })
+}
+
+fn derive_visit_variant_fields_concurrent(
+    ast: &DeriveInput,
+    data: &DataEnum,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(syn::parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::ForkVisitor });
+    push_field_bounds(
+        predicates,
+        ast,
+        data.variants.iter().flat_map(|variant| &variant.fields),
+        BoundKind::VisitAsync,
+        |field| {
+            let ty = &field.ty;
+            let mut inferred = vec![parse_quote! { for<'__visit_rs__sync> #ty: Sync }];
+            inferred.extend(
+                with::bound(field, BoundKind::VisitAsync, false, None).unwrap_or_else(|| {
+                    vec![parse_quote! { #ty: visit_rs::VisitAsync<__visit_rs__V> }]
+                }),
+            );
+            inferred
+        },
+    )?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let field_counts = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+        let count = variant.fields.len();
+        quote! { Self::#variant_name { .. } => #count }
+    });
+
+    let variant_arms = data.variants.iter().map(|variant| {
+        let variant_name = &variant.ident;
+
+        let members: Vec<_> = variant
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| match &field.ident {
+                Some(field_name) => quote! { #field_name },
+                None => {
+                    let index = syn::Index::from(idx);
+                    quote! { #index }
+                }
+            })
+            .collect();
+        let values: Vec<_> = (0..members.len())
+            .map(|idx| Ident::new(&format!("__visit_rs__value{idx}"), Span::call_site()))
+            .collect();
+
+        let field_matches =
+            values
+                .iter()
+                .zip(&variant.fields)
+                .enumerate()
+                .map(|(idx, (value, field))| {
+                    let value = with::value(field, quote! { #value }, BoundKind::VisitAsync);
+                    quote! {
+                        #idx => (#idx, visit_rs::VisitAsync::visit_async(#value, visitor).await)
+                    }
+                });
+
+        quote! {
+            Self::#variant_name { #(#members: #values,)* } => match pos {
+                #(#field_matches,)*
+                _ => unreachable!(),
+            }
+        }
+    });
+
+    let stream = concurrent_stream(
+        quote! {
+            0..match self {
+                #(#field_counts),*
+            }
+        },
+        quote! {
+            match self {
+                #(#variant_arms),*
+            }
+        },
+    );
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::VisitVariantFieldsConcurrent<__visit_rs__V> for #ident #ty_generics #where_clause {
+            fn visit_variant_fields_concurrent<'a>(
+                &'a self,
+                visitor: &__visit_rs__V,
+                limit: usize,
+            ) -> impl visit_rs::lib::futures::Stream<Item = (usize, <__visit_rs__V as visit_rs::Visitor>::Result)> + Send + 'a
+            where
+                __visit_rs__V: Send + 'a,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                #stream
+            }
+        }
+    })
+}
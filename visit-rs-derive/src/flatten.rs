@@ -0,0 +1,136 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Field, Fields};
+
+use crate::helpers::has_visit_flag;
+use crate::{field_iter, yield_visit};
+
+/// Whether the fields of `field` are visited in its place
+pub fn is_flattened(field: &Field) -> bool {
+    has_visit_flag(&field.attrs, "flatten")
+}
+
+/// Reject `flatten` where it cannot apply
+pub fn check_fields<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Result<(), syn::Error> {
+    for field in fields {
+        if is_flattened(field) && crate::with::is_visited_with_any(field) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "a flattened field is visited through its own fields, so it takes no `with` or `visit_with`",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A `match pos` arm yielding every result of `iter`, the iterator over the fields
+/// of the flattened field visited at position `num`, from the stream
+/// [`sync_iter`] builds around it
+pub fn arm(num: usize, iter: TokenStream) -> TokenStream {
+    quote! {
+        #num => {
+            for res in #iter {
+                yield res;
+            }
+            pos += 1;
+            continue;
+        }
+    }
+}
+
+/// The iterator of a sync visit of `fields`, ending at the first break, that
+/// evaluates the `match pos` `arms` for the field visited at each position
+pub fn sync_iter(fields: &Fields, arms: impl Iterator<Item = TokenStream>) -> TokenStream {
+    sync_iter_skipping(fields, arms, false)
+}
+
+/// [`sync_iter`], where arms may also `continue` past a field when `skipping`
+///
+/// With a flattened field, the visit is a stream that never waits, so that the
+/// iterator over its fields can borrow the visitor until it runs out.
+pub fn sync_iter_skipping(
+    fields: &Fields,
+    arms: impl Iterator<Item = TokenStream>,
+    skipping: bool,
+) -> TokenStream {
+    let next = quote! {
+        match pos {
+            #(#arms)*
+            _ => None,
+        }
+    };
+    if field_iter(fields).any(|(_, field)| is_flattened(field)) {
+        return quote! {
+            visit_rs::UntilBreak::<__visit_rs__V, _>::new(visit_rs::__StreamIter::new(
+                visit_rs::lib::async_stream::stream! {
+                    let mut pos = 0;
+                    loop {
+                        let next: Option<_> = #next;
+                        match next {
+                            Some(res) => yield res,
+                            None => break,
+                        }
+                    }
+                },
+            ))
+        };
+    }
+    // Moving past a skipped field must try the next position
+    let next = if skipping {
+        quote! { loop { break #next; } }
+    } else {
+        next
+    };
+    quote! {
+        visit_rs::UntilBreak::<__visit_rs__V, _>::new(std::iter::from_fn({
+            let mut pos = 0;
+            move || #next
+        }))
+    }
+}
+
+/// Yield every result of `stream`, the stream over a flattened field's fields, from
+/// inside `stream!`, ending the stream if the visitor breaks
+pub fn yield_stream(stream: TokenStream) -> TokenStream {
+    let yield_res = yield_visit(quote! { res });
+    quote! {
+        {
+            let mut fields = std::pin::pin!(#stream);
+            while let Some(res) = visit_rs::lib::futures::StreamExt::next(&mut fields).await {
+                #yield_res
+            }
+        }
+    }
+}
+
+/// The positions of the visited fields of a struct, split into runs of fields
+/// visited one at a time and single flattened fields, in order
+pub enum Segment {
+    Fields(Vec<usize>),
+    Flattened(usize),
+}
+
+pub fn segments(fields: &Fields) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for (num, (_, field)) in field_iter(fields).enumerate() {
+        if is_flattened(field) {
+            segments.push(Segment::Flattened(num));
+        } else if let Some(Segment::Fields(run)) = segments.last_mut() {
+            run.push(num);
+        } else {
+            segments.push(Segment::Fields(vec![num]));
+        }
+    }
+    if segments.is_empty() {
+        segments.push(Segment::Fields(Vec::new()));
+    }
+    segments
+}
+
+/// Chain the iterators or streams `parts` with `chain`, e.g.
+/// `visit_rs::lib::futures::StreamExt::chain`
+pub fn chain(chain: TokenStream, parts: impl IntoIterator<Item = TokenStream>) -> TokenStream {
+    let mut parts = parts.into_iter();
+    let first = parts.next().unwrap_or_default();
+    parts.fold(first, |acc, part| quote! { #chain(#acc, #part) })
+}
@@ -0,0 +1,121 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Field, Path, parse_quote};
+
+use crate::helpers::{attr_items, has_visit_flag, str_values};
+
+/// The attribute [`mark_skipped`] puts on fields serde skips. Derives only see a
+/// field's own attributes, so this carries the container's `follow_serde` to them
+/// without adding to the metadata they report
+const SKIPPED: &str = "__visit_rs_serde_skipped";
+
+/// Whether the container visits only the fields serde would serialize
+pub fn is_enabled(ast: &DeriveInput) -> bool {
+    has_visit_flag(&ast.attrs, "follow_serde")
+}
+
+/// Whether serde never serializes `field`
+fn is_serde_skipped(field: &Field) -> bool {
+    attr_items(&field.attrs, &["visit", "serde"])
+        .iter()
+        .any(|meta| {
+            matches!(meta, syn::Meta::Path(path)
+                if path.is_ident("skip") || path.is_ident("skip_serializing"))
+        })
+}
+
+/// Under `#[visit(follow_serde)]`, mark every field serde skips as skipped
+///
+/// Variant fields cannot be skipped, so an enum is an error.
+pub fn mark_skipped(ast: &mut DeriveInput) -> Result<(), syn::Error> {
+    if !is_enabled(ast) {
+        return Ok(());
+    }
+    let fields: Box<dyn Iterator<Item = &mut Field>> = match &mut ast.data {
+        syn::Data::Struct(data) => Box::new(data.fields.iter_mut()),
+        syn::Data::Union(data) => Box::new(data.fields.named.iter_mut()),
+        syn::Data::Enum(_) => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "`follow_serde` is only supported on structs and unions",
+            ));
+        }
+    };
+    let marker = syn::Ident::new(SKIPPED, proc_macro2::Span::call_site());
+    for field in fields {
+        if is_serde_skipped(field) {
+            field.attrs.push(parse_quote! { #[#marker] });
+        }
+    }
+    Ok(())
+}
+
+/// Whether `field` was marked by [`mark_skipped`]
+pub fn is_marked(field: &Field) -> bool {
+    field.attrs.iter().any(|attr| attr.path().is_ident(SKIPPED))
+}
+
+/// Under `#[visit(follow_serde)]`, the predicate of a field's
+/// `skip_serializing_if = "path"`, called with a reference to the field
+pub fn skip_if(ast: &DeriveInput, field: &Field) -> Result<Option<Path>, syn::Error> {
+    if !is_enabled(ast) {
+        return Ok(None);
+    }
+    let items = attr_items(&field.attrs, &["visit", "serde"]);
+    let Some(path) = str_values(&items, "skip_serializing_if").next() else {
+        return Ok(None);
+    };
+    syn::parse_str(&path).map(Some).map_err(|_| {
+        syn::Error::new_spanned(
+            field,
+            format!("`skip_serializing_if` expects a path to a function, found {path:?}"),
+        )
+    })
+}
+
+/// Wrap `visit`, evaluated for the field `idx`, so it is passed over if the
+/// field's `skip_serializing_if` predicate holds, running `skip` instead
+pub fn guard(
+    ast: &DeriveInput,
+    field: &Field,
+    idx: &TokenStream,
+    visit: TokenStream,
+    skip: TokenStream,
+) -> Result<TokenStream, syn::Error> {
+    Ok(match skip_if(ast, field)? {
+        Some(predicate) => quote! {
+            if #predicate(&self.#idx) {
+                #skip
+            } else {
+                #visit
+            }
+        },
+        None => visit,
+    })
+}
+
+/// An iterator over the positions `0..` of `fields`, each given with the member
+/// visited there, passing over those whose `skip_serializing_if` predicate holds
+pub fn positions<'a>(
+    ast: &DeriveInput,
+    fields: impl IntoIterator<Item = (&'a Field, &'a TokenStream)>,
+) -> Result<TokenStream, syn::Error> {
+    let mut count = 0usize;
+    let mut arms = Vec::new();
+    for (pos, (field, idx)) in fields.into_iter().enumerate() {
+        count += 1;
+        if let Some(predicate) = skip_if(ast, field)? {
+            arms.push(quote! { #pos => !#predicate(&self.#idx), });
+        }
+    }
+    Ok(if arms.is_empty() {
+        quote! { 0..#count }
+    } else {
+        quote! {
+            (0..#count).filter(|pos| match *pos {
+                #(#arms)*
+                _ => true,
+            })
+        }
+    })
+}
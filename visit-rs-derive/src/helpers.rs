@@ -1,4 +1,10 @@
-use syn::{DeriveInput, Lit, Meta, Variant};
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, DeriveInput, Expr, ExprLit, Lit, Meta, Path, Token, Variant};
 
 #[derive(Debug, Clone, Copy)]
 pub enum RenameRule {
@@ -14,6 +20,18 @@ pub enum RenameRule {
 }
 
 impl RenameRule {
+    /// The names rules are written as
+    pub const NAMES: [&'static str; 8] = [
+        "lowercase",
+        "UPPERCASE",
+        "PascalCase",
+        "camelCase",
+        "snake_case",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+        "SCREAMING-KEBAB-CASE",
+    ];
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "lowercase" => Some(RenameRule::LowerCase),
@@ -93,121 +111,277 @@ fn to_kebab_case(s: &str) -> String {
     to_snake_case(s).replace('_', "-")
 }
 
-pub fn get_rename_attribute(ast: &DeriveInput) -> Option<String> {
-    for attr in &ast.attrs {
-        // Check for #[visit(rename = "...")]
-        if attr.path().is_ident("visit") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return Some(s.value());
-                            }
-                        }
-                    }
+/// The entries of every attribute in `attrs` whose name is one of `names`, in
+/// order, e.g. both `a` and `b = 1` for `#[visit(a, b = 1)]`. Attributes that do
+/// not parse as a list of metas contribute nothing
+pub fn attr_items(attrs: &[Attribute], names: &[&str]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| names.iter().any(|name| attr.path().is_ident(name)))
+        .filter_map(|attr| {
+            attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .collect()
+}
+
+/// The entries of every `#[visit(...)]` attribute in `attrs`
+pub fn visit_items(attrs: &[Attribute]) -> Vec<Meta> {
+    attr_items(attrs, &["visit"])
+}
+
+/// The value of a string literal
+fn lit_str(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Some(s.value()),
+        _ => None,
+    }
+}
+
+/// The values of every `key = "..."` among `items`
+pub fn str_values<'a>(items: &'a [Meta], key: &'a str) -> impl Iterator<Item = String> + 'a {
+    items.iter().filter_map(move |meta| match meta {
+        Meta::NameValue(nv) if nv.path.is_ident(key) => lit_str(&nv.value),
+        _ => None,
+    })
+}
+
+/// A name or rule for each direction, as serde allows `rename = "..."` to be
+/// split into `rename(serialize = "...", deserialize = "...")`
+#[derive(Debug, Clone, Default)]
+struct SerDe<T> {
+    serialize: Option<T>,
+    deserialize: Option<T>,
+}
+
+/// The first `key = "..."` or `key(serialize = "...", deserialize = "...")` among
+/// `items`, for each direction
+fn ser_de_values(items: &[Meta], key: &str) -> SerDe<String> {
+    let mut values = SerDe::default();
+    for meta in items {
+        match meta {
+            Meta::NameValue(nv) if nv.path.is_ident(key) => {
+                if let Some(value) = lit_str(&nv.value) {
+                    values.serialize.get_or_insert_with(|| value.clone());
+                    values.deserialize.get_or_insert(value);
                 }
             }
-        }
-        // Check for #[serde(rename = "...")]
-        if attr.path().is_ident("serde") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return Some(s.value());
-                            }
-                        }
-                    }
+            Meta::List(list) if list.path.is_ident(key) => {
+                let Ok(nested) =
+                    list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                else {
+                    continue;
+                };
+                let nested: Vec<_> = nested.into_iter().collect();
+                if let Some(value) = str_values(&nested, "serialize").next() {
+                    values.serialize.get_or_insert(value);
                 }
-            }
-        }
-    }
-    None
-}
-
-pub fn get_rename_all_attribute(ast: &DeriveInput) -> RenameRule {
-    for attr in &ast.attrs {
-        // Check for #[visit(rename_all = "...")]
-        if attr.path().is_ident("visit") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename_all") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                if let Some(rule) = RenameRule::from_str(&s.value()) {
-                                    return rule;
-                                }
-                            }
-                        }
-                    }
+                if let Some(value) = str_values(&nested, "deserialize").next() {
+                    values.deserialize.get_or_insert(value);
                 }
             }
+            _ => {}
         }
-        // Check for #[serde(rename_all = "...")]
-        if attr.path().is_ident("serde") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename_all") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                if let Some(rule) = RenameRule::from_str(&s.value()) {
-                                    return rule;
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    }
+    values
+}
+
+/// The `rename_all`-style rules for each direction, `None` where unset
+#[derive(Debug, Clone, Copy)]
+pub struct RenameRules {
+    pub serialize: RenameRule,
+    pub deserialize: RenameRule,
+}
+
+impl RenameRules {
+    /// Each direction's rule, or `other`'s where this one is unset
+    fn or(self, other: Self) -> Self {
+        let pick = |rule, other| match rule {
+            RenameRule::None => other,
+            rule => rule,
+        };
+        RenameRules {
+            serialize: pick(self.serialize, other.serialize),
+            deserialize: pick(self.deserialize, other.deserialize),
         }
     }
-    RenameRule::None
 }
 
-pub fn get_variant_rename(variant: &Variant, default_rule: RenameRule) -> String {
+/// Parse the `key = "..."` or `key(serialize = "...", deserialize = "...")` rules
+/// in `#[visit(...)]` or `#[serde(...)]`
+fn rename_rules(attrs: &[Attribute], key: &str) -> RenameRules {
+    let SerDe {
+        serialize,
+        deserialize,
+    } = ser_de_values(&attr_items(attrs, &["visit", "serde"]), key);
+    let rule = |rule: Option<String>| {
+        rule.and_then(|rule| RenameRule::from_str(&rule))
+            .unwrap_or(RenameRule::None)
+    };
+    RenameRules {
+        serialize: rule(serialize),
+        deserialize: rule(deserialize),
+    }
+}
+
+/// The explicit `rename` of an item, for each direction
+fn renames(attrs: &[Attribute]) -> SerDe<String> {
+    ser_de_values(&attr_items(attrs, &["visit", "serde"]), "rename")
+}
+
+/// The names an item is accepted by besides `name`, the one it is visited as: the
+/// name it deserializes from, then each `alias`, without duplicates
+fn aliases(attrs: &[Attribute], name: &str, deserialize: String) -> Vec<String> {
+    let mut aliases = vec![deserialize];
+    aliases.extend(str_values(&attr_items(attrs, &["visit", "serde"]), "alias"));
+    let mut seen = HashSet::from([name.to_string()]);
+    aliases.retain(|alias| seen.insert(alias.clone()));
+    aliases
+}
+
+/// Parse `#[visit(rename = "...")]` or `#[serde(rename = "...")]`, the name the
+/// container serializes as
+pub fn get_rename_attribute(ast: &DeriveInput) -> Option<String> {
+    renames(&ast.attrs).serialize
+}
+
+/// Parse `#[visit(rename_all = "...")]` or `#[serde(rename_all = "...")]`, which
+/// renames the fields of a struct or the variants of an enum
+pub fn get_rename_all_attribute(ast: &DeriveInput) -> RenameRules {
+    rename_rules(&ast.attrs, "rename_all")
+}
+
+/// The rules renaming the fields of `variant`: its own `rename_all`, else the
+/// enum's `rename_all_fields`, for each direction
+pub fn get_variant_fields_rules(ast: &DeriveInput, variant: &Variant) -> RenameRules {
+    rename_rules(&variant.attrs, "rename_all").or(rename_rules(&ast.attrs, "rename_all_fields"))
+}
+
+/// The name `variant` serializes as, which it is visited and looked up as
+pub fn get_variant_rename(variant: &Variant, rules: RenameRules) -> String {
     // First check for explicit rename attribute
-    for attr in &variant.attrs {
-        if attr.path().is_ident("visit") || attr.path().is_ident("serde") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return s.value();
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if let Some(renamed) = renames(&variant.attrs).serialize {
+        return renamed;
     }
 
     // Apply rename_all rule
-    default_rule.apply(&variant.ident.to_string())
+    rules.serialize.apply(&variant.ident.to_string())
+}
+
+/// The other names `variant` is looked up by, see [`aliases`]
+pub fn get_variant_aliases(variant: &Variant, rules: RenameRules) -> Vec<String> {
+    let deserialize = renames(&variant.attrs)
+        .deserialize
+        .unwrap_or_else(|| rules.deserialize.apply(&variant.ident.to_string()));
+    aliases(
+        &variant.attrs,
+        &get_variant_rename(variant, rules),
+        deserialize,
+    )
+}
+
+/// The name of `field` without any `r#`, as serde names it
+fn field_ident_name(field: &syn::Field) -> Option<String> {
+    Some(field.ident.as_ref()?.unraw().to_string())
 }
 
-pub fn get_field_rename(field: &syn::Field, default_rule: RenameRule) -> Option<String> {
-    let field_name = field.ident.as_ref()?.to_string();
+/// The name `field` serializes as, which it is visited and looked up as
+pub fn get_field_rename(field: &syn::Field, rules: RenameRules) -> Option<String> {
+    let field_name = field_ident_name(field)?;
 
     // First check for explicit rename attribute
-    for attr in &field.attrs {
-        if attr.path().is_ident("visit") || attr.path().is_ident("serde") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return Some(s.value());
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if let Some(renamed) = renames(&field.attrs).serialize {
+        return Some(renamed);
     }
 
     // Apply rename_all rule
-    Some(default_rule.apply(&field_name))
+    Some(rules.serialize.apply(&field_name))
+}
+
+/// The other names `field` is looked up by, see [`aliases`]
+pub fn get_field_aliases(field: &syn::Field, rules: RenameRules) -> Vec<String> {
+    let (Some(field_name), Some(name)) = (field_ident_name(field), get_field_rename(field, rules))
+    else {
+        return Vec::new();
+    };
+    let deserialize = renames(&field.attrs)
+        .deserialize
+        .unwrap_or_else(|| rules.deserialize.apply(&field_name));
+    aliases(&field.attrs, &name, deserialize)
+}
+
+/// Check for a bare `flag` in a `#[visit(...)]` attribute
+pub fn has_visit_flag(attrs: &[Attribute], flag: &str) -> bool {
+    visit_items(attrs)
+        .iter()
+        .any(|meta| matches!(meta, Meta::Path(path) if path.is_ident(flag)))
+}
+
+/// Parse `#[visit(crate = "...")]`, the path generated code uses to reach visit-rs
+pub fn get_crate_path(attrs: &[Attribute]) -> Result<Option<Path>, syn::Error> {
+    for meta in visit_items(attrs) {
+        if let Meta::NameValue(nv) = meta
+            && nv.path.is_ident("crate")
+        {
+            return match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(s), ..
+                }) => s.parse().map(Some),
+                value => Err(syn::Error::new_spanned(
+                    value,
+                    "expected a string literal, e.g. `crate = \"my_crate::visit_rs\"`",
+                )),
+            };
+        }
+    }
+    Ok(None)
+}
+
+/// Wrap the generated impls in an anonymous const, aliasing the `crate` path (if
+/// any) as `visit_rs` so every generated `visit_rs::...` path resolves through it
+///
+/// Without the attribute, `visit_rs` resolves at the derive site, so a crate that
+/// re-exports visit-rs as `visit_rs` (e.g. in a prelude its users glob-import)
+/// makes the derive work for them without any attribute.
+pub fn wrap_impls(ast: &DeriveInput, impls: TokenStream) -> Result<TokenStream, syn::Error> {
+    let use_crate = get_crate_path(&ast.attrs)?
+        .filter(|path| !path.is_ident("visit_rs"))
+        .map(|path| quote! { use #path as visit_rs; });
+    Ok(quote! {
+        const _: () = {
+            #use_crate
+            #impls
+        };
+    })
 }
 
+/// Render a type the way it is usually written, e.g. `Vec<u8>` rather than `Vec < u8 >`
+pub fn type_name(ty: &syn::Type) -> String {
+    source_text(ty)
+}
+
+/// Render tokens the way they are usually written, tightening the spacing
+/// `to_string` puts around punctuation
+pub fn source_text(tokens: &impl quote::ToTokens) -> String {
+    let mut name = tokens.to_token_stream().to_string();
+    for (from, to) in [
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        (" ;", ";"),
+        (" ::", "::"),
+        (":: ", "::"),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+        ("[ ", "["),
+        (" ]", "]"),
+    ] {
+        name = name.replace(from, to);
+    }
+    name
+}
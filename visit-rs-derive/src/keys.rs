@@ -0,0 +1,335 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Expr, ExprLit, Lit, Meta, Token};
+
+use crate::helpers::{RenameRule, visit_items};
+
+/// Where an attribute is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    Struct,
+    Union,
+    Enum,
+    Variant,
+    /// A field of a struct or union
+    Field,
+    VariantField,
+}
+
+impl Position {
+    fn describe(self) -> &'static str {
+        match self {
+            Position::Struct => "structs",
+            Position::Union => "unions",
+            Position::Enum => "enums",
+            Position::Variant => "variants",
+            Position::Field => "struct fields",
+            Position::VariantField => "variant fields",
+        }
+    }
+}
+
+/// How the value of a key is written
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    /// A bare `key`
+    Flag,
+    /// `key = "..."`
+    Str,
+    /// `key = "..."` or `key(serialize = "...", deserialize = "...")`
+    SerDe,
+    /// As [`Shape::SerDe`], each a rule like `"camelCase"`
+    Rule,
+    /// `key = "..."` or `key(<kind> = "...", ...)`, checked where bounds are parsed
+    Bound,
+}
+
+/// A key `#[visit(...)]` understands
+struct Key {
+    name: &'static str,
+    shape: Shape,
+    positions: &'static [Position],
+    /// Whether the key may be given more than once on one item
+    repeatable: bool,
+}
+
+use Position::{Enum, Field, Struct, Union, Variant, VariantField};
+
+const fn key(name: &'static str, shape: Shape, positions: &'static [Position]) -> Key {
+    Key {
+        name,
+        shape,
+        positions,
+        repeatable: false,
+    }
+}
+
+const KEYS: &[Key] = &[
+    key("crate", Shape::Str, &[Struct, Union, Enum]),
+    Key {
+        repeatable: true,
+        ..key(
+            "bound",
+            Shape::Bound,
+            &[Struct, Union, Enum, Field, VariantField],
+        )
+    },
+    key(
+        "rename",
+        Shape::SerDe,
+        &[Struct, Union, Enum, Variant, Field, VariantField],
+    ),
+    key("rename_all", Shape::Rule, &[Struct, Union, Enum, Variant]),
+    key("rename_all_fields", Shape::Rule, &[Enum]),
+    Key {
+        repeatable: true,
+        ..key("alias", Shape::Str, &[Variant, Field])
+    },
+    key("follow_serde", Shape::Flag, &[Struct, Union]),
+    key("owned", Shape::Flag, &[Struct, Enum]),
+    key("unit_variants", Shape::Flag, &[Enum]),
+    key("from_str", Shape::Flag, &[Enum]),
+    key("display", Shape::Flag, &[Enum]),
+    key("skip", Shape::Flag, &[Field]),
+    key("skip_serializing", Shape::Flag, &[Field]),
+    key("skip_serializing_if", Shape::Str, &[Field]),
+    key("flatten", Shape::Flag, &[Field]),
+    key("with", Shape::Str, &[Field, VariantField]),
+    key("visit_with", Shape::Str, &[Field, VariantField]),
+];
+
+/// The number of single-character edits, counting swapping neighbours as one,
+/// between `a` and `b`
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The candidate closest to `name`, if it is near enough to be a misspelling
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// ", did you mean `...`?" if `name` is near one of `candidates`
+fn suggestion<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    did_you_mean(name, candidates)
+        .map(|candidate| format!(", did you mean `{candidate}`?"))
+        .unwrap_or_default()
+}
+
+/// The key valid at `position` that `meta`, an unknown key `name`, misspells
+///
+/// Only a bare `name`, one edit away from such a key, counts: custom keys are
+/// kept as metadata, and usually carry a value.
+fn typo_of(meta: &Meta, name: &str, position: Position) -> Option<&'static str> {
+    if !matches!(meta, Meta::Path(_)) {
+        return None;
+    }
+    KEYS.iter()
+        .filter(|key| key.positions.contains(&position))
+        .find(|key| distance(name, key.name) == 1)
+        .map(|key| key.name)
+}
+
+/// Check a string value, and that it names a rule if `rule` is set
+fn check_str(expr: &Expr, key: &str, rule: bool) -> Result<(), syn::Error> {
+    let Expr::Lit(ExprLit {
+        lit: Lit::Str(s), ..
+    }) = expr
+    else {
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!("expected a string literal, e.g. `{key} = \"...\"`"),
+        ));
+    };
+    if rule && RenameRule::from_str(&s.value()).is_none() {
+        let value = s.value();
+        return Err(syn::Error::new_spanned(
+            s,
+            match did_you_mean(&value, RenameRule::NAMES) {
+                Some(name) => format!("unknown rename rule `{value}`, did you mean `{name}`?"),
+                None => format!(
+                    "unknown rename rule `{value}`, expected one of: {}",
+                    RenameRule::NAMES.join(", ")
+                ),
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Check the value of `meta`, an entry for `key`
+fn check_shape(meta: &Meta, key: &Key) -> Result<(), syn::Error> {
+    let name = key.name;
+    match (key.shape, meta) {
+        (Shape::Flag, Meta::Path(_)) => Ok(()),
+        (Shape::Flag, _) => Err(syn::Error::new_spanned(
+            meta,
+            format!("`{name}` takes no value, write `#[visit({name})]`"),
+        )),
+        (Shape::Bound, Meta::NameValue(_) | Meta::List(_)) => Ok(()),
+        (Shape::Str | Shape::SerDe | Shape::Rule, Meta::NameValue(nv)) => {
+            check_str(&nv.value, name, matches!(key.shape, Shape::Rule))
+        }
+        (Shape::SerDe | Shape::Rule, Meta::List(list)) => {
+            let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            let mut seen = Vec::new();
+            for meta in nested {
+                let Meta::NameValue(nv) = &meta else {
+                    return Err(syn::Error::new_spanned(
+                        &meta,
+                        format!(
+                            "expected `serialize = \"...\"` or `deserialize = \"...\"` in `{name}(...)`"
+                        ),
+                    ));
+                };
+                let direction = nv
+                    .path
+                    .get_ident()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                if direction != "serialize" && direction != "deserialize" {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        format!(
+                            "expected `serialize` or `deserialize` in `{name}(...)`{}",
+                            suggestion(&direction, ["serialize", "deserialize"])
+                        ),
+                    ));
+                }
+                if seen.contains(&direction) {
+                    return Err(syn::Error::new_spanned(
+                        &nv.path,
+                        format!("duplicate `{direction}` in `{name}(...)`"),
+                    ));
+                }
+                check_str(&nv.value, &direction, matches!(key.shape, Shape::Rule))?;
+                seen.push(direction);
+            }
+            Ok(())
+        }
+        (_, _) => Err(syn::Error::new_spanned(
+            meta,
+            match key.shape {
+                Shape::Str => format!("expected `{name} = \"...\"`"),
+                _ => format!("expected `{name} = \"...\"` or `{name}(...)`"),
+            },
+        )),
+    }
+}
+
+/// Check every `#[visit(...)]` entry among `attrs`, written at `position`
+///
+/// Keys that are not understood are kept as metadata, unless they look like a
+/// misspelling of one that applies at `position`.
+fn check_attrs(attrs: &[Attribute], position: Position) -> Result<(), syn::Error> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("visit")) {
+        if !matches!(attr.meta, Meta::List(_)) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected a list of keys, e.g. `#[visit(rename = \"...\")]`",
+            ));
+        }
+        attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+    }
+
+    let mut seen: Vec<&str> = Vec::new();
+    for meta in visit_items(attrs) {
+        let name = meta
+            .path()
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let Some(key) = KEYS.iter().find(|key| key.name == name) else {
+            if let Some(known) = typo_of(&meta, &name, position) {
+                return Err(syn::Error::new_spanned(
+                    meta.path(),
+                    format!("unknown `visit` key `{name}`, did you mean `{known}`?"),
+                ));
+            }
+            continue;
+        };
+        if !key.positions.contains(&position) {
+            let applies_to: Vec<_> = key.positions.iter().map(|p| p.describe()).collect();
+            return Err(syn::Error::new_spanned(
+                meta.path(),
+                format!(
+                    "`{name}` is not supported on {}, only on {}",
+                    position.describe(),
+                    applies_to.join(" and ")
+                ),
+            ));
+        }
+        check_shape(&meta, key)?;
+        if !key.repeatable && seen.contains(&key.name) {
+            return Err(syn::Error::new_spanned(
+                meta.path(),
+                format!("duplicate `{name}`"),
+            ));
+        }
+        seen.push(key.name);
+    }
+    Ok(())
+}
+
+/// Check the `#[visit(...)]` attributes on `ast`, its variants and its fields,
+/// reporting every error found
+///
+/// `#[serde(...)]` attributes are left to serde.
+pub fn check(ast: &DeriveInput) -> Result<(), syn::Error> {
+    let mut items = Vec::new();
+    match &ast.data {
+        Data::Struct(data) => {
+            items.push((&ast.attrs, Struct));
+            items.extend(data.fields.iter().map(|field| (&field.attrs, Field)));
+        }
+        Data::Enum(data) => {
+            items.push((&ast.attrs, Enum));
+            for variant in &data.variants {
+                items.push((&variant.attrs, Variant));
+                items.extend(
+                    variant
+                        .fields
+                        .iter()
+                        .map(|field| (&field.attrs, VariantField)),
+                );
+            }
+        }
+        Data::Union(data) => {
+            items.push((&ast.attrs, Union));
+            items.extend(data.fields.named.iter().map(|field| (&field.attrs, Field)));
+        }
+    }
+    items
+        .into_iter()
+        .filter_map(|(attrs, position)| check_attrs(attrs, position).err())
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        })
+        .map_or(Ok(()), Err)
+}
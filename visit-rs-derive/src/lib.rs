@@ -1,49 +1,21 @@
-use std::collections::HashSet;
-
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{
-    DataStruct, DeriveInput, Fields, Ident, Lit, Meta, Path, WhereClause, WherePredicate,
-    parse_quote,
-};
+use std::collections::HashSet;
+use syn::{DataStruct, DeriveInput, Fields, Ident, Path, WherePredicate, parse_quote};
 
 mod attrs;
+mod bounds;
+mod flatten;
+mod follow_serde;
 mod helpers;
-use helpers::{get_field_rename, get_rename_all_attribute};
-
-fn get_rename_attribute(ast: &DeriveInput) -> Option<String> {
-    for attr in &ast.attrs {
-        // Check for #[visit(rename = "...")]
-        if attr.path().is_ident("visit") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return Some(s.value());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        // Check for #[serde(rename = "...")]
-        if attr.path().is_ident("serde") {
-            if let Ok(meta_list) = attr.meta.require_list() {
-                if let Ok(Meta::NameValue(nv)) = syn::parse2::<Meta>(meta_list.tokens.clone()) {
-                    if nv.path.is_ident("rename") {
-                        if let syn::Expr::Lit(lit) = &nv.value {
-                            if let Lit::Str(s) = &lit.lit {
-                                return Some(s.value());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-    None
-}
+mod keys;
+mod with;
+use bounds::{BoundKind, push_field_bounds};
+use flatten::Segment;
+use helpers::{
+    RenameRules, get_field_aliases, get_field_rename, get_rename_all_attribute,
+    get_rename_attribute, has_visit_flag, type_name, wrap_impls,
+};
 
 fn make_impl(
     input: &DeriveInput,
@@ -53,7 +25,7 @@ fn make_impl(
     named: Option<&Path>,
     sync: bool,
     is_static: bool,
-) -> TokenStream {
+) -> Result<TokenStream, syn::Error> {
     let ident = &input.ident;
 
     let (_, ty_generics, _) = &input.generics.split_for_impl();
@@ -62,59 +34,74 @@ fn make_impl(
 
     generics.params.push(syn::parse_quote! { __visit_rs__V });
 
-    let predicates = &mut generics
-        .where_clause
-        .get_or_insert(WhereClause {
-            predicates: Default::default(),
-            where_token: Default::default(),
-        })
-        .predicates;
+    let predicates = &mut generics.make_where_clause().predicates;
 
     predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::Visitor });
-    if sync {
-        predicates.extend(fields.iter().map(|f| &f.ty).map(|t| -> WherePredicate {
-            parse_quote! { #t: Sync }
-        }));
-    }
-
-    let mut ty_set = HashSet::new();
-    for (_, field) in field_iter(fields) {
+    let kind = BoundKind::of_trait(trait_path);
+    push_field_bounds(predicates, input, fields, kind, |field| {
         let ty = &field.ty;
-        if !ty_set.insert(ty) {
-            continue;
+        let mut inferred: Vec<WherePredicate> = Vec::new();
+        if sync {
+            // Higher-ranked so that a non-`Sync` field disables the impl rather than
+            // failing to compile, leaving the type usable with the `*Local` traits
+            inferred.push(parse_quote! { for<'__visit_rs__sync> #ty: Sync });
         }
-        if let Some(named) = named {
-            if is_static {
-                predicates.push(
-                    syn::parse_quote! { for<'__visit_rs__named> #named <'__visit_rs__named, visit_rs::Static<#ty>>: #trait_path<__visit_rs__V> },
-                );
-            } else {
-                predicates.push(syn::parse_quote! { for<'__visit_rs__named> #named <'__visit_rs__named, #ty>: #trait_path<__visit_rs__V> });
-            }
-        } else {
-            if is_static {
-                predicates
-                    .push(syn::parse_quote! { visit_rs::Static<#ty>: #trait_path<__visit_rs__V> });
-            } else {
-                predicates.push(syn::parse_quote! { #ty: #trait_path<__visit_rs__V> });
-            }
+        if is_skipped(field) {
+            return inferred;
         }
-    }
+        if flatten::is_flattened(field) {
+            inferred.push(parse_quote! { #ty: #trait_path_fields<__visit_rs__V> });
+            return inferred;
+        }
+        if let Some(bound) = with::bound(field, kind, is_static, named.map(|n| quote! { #n })) {
+            inferred.extend(bound);
+            return inferred;
+        }
+        inferred.push(match (named, is_static) {
+            (Some(named), true) => parse_quote! {
+                for<'__visit_rs__named> #named <'__visit_rs__named, visit_rs::Static<#ty>>: #trait_path<__visit_rs__V>
+            },
+            (Some(named), false) => parse_quote! {
+                for<'__visit_rs__named> #named <'__visit_rs__named, #ty>: #trait_path<__visit_rs__V>
+            },
+            (None, true) => parse_quote! { visit_rs::Static<#ty>: #trait_path<__visit_rs__V> },
+            (None, false) => parse_quote! { #ty: #trait_path<__visit_rs__V> },
+        });
+        inferred
+    })?;
 
     let (impl_generics, _, where_clause) = generics.split_for_impl();
 
-    quote! {
+    Ok(quote! {
         impl #impl_generics #trait_path_fields<__visit_rs__V> for #ident #ty_generics #where_clause
+    })
+}
+
+/// Yield a visit result from inside `stream!`, ending the stream if the visitor breaks
+fn yield_visit(visit: TokenStream) -> TokenStream {
+    quote! {
+        {
+            let res = #visit;
+            let is_break = <__visit_rs__V as visit_rs::Visitor>::is_break(&res);
+            yield res;
+            if is_break {
+                return;
+            }
+        }
     }
 }
 
+/// Whether `field` is left out of visits, by `#[visit(skip)]` or, under
+/// `#[visit(follow_serde)]`, because serde skips it
+fn is_skipped(field: &syn::Field) -> bool {
+    has_visit_flag(&field.attrs, "skip") || follow_serde::is_marked(field)
+}
+
 fn field_iter(fields: &Fields) -> impl Iterator<Item = (usize, &syn::Field)> {
-    fields.iter().enumerate().filter(|(_, field)| {
-        !field.attrs.iter().any(|attr| {
-            attr.path().is_ident("visit")
-                && attr.parse_args::<Ident>().map_or(false, |id| id == "skip")
-        })
-    })
+    fields
+        .iter()
+        .enumerate()
+        .filter(|(_, field)| !is_skipped(field))
 }
 
 fn field_idx_iter(fields: &Fields) -> impl Iterator<Item = TokenStream> {
@@ -129,6 +116,21 @@ fn field_idx_iter(fields: &Fields) -> impl Iterator<Item = TokenStream> {
     })
 }
 
+/// A reference to each visited field for the impls of `kind`, through its adapter
+/// if it is visited `with` a function
+fn field_value_iter(fields: &Fields, kind: BoundKind) -> impl Iterator<Item = TokenStream> {
+    field_idx_iter(fields)
+        .zip(field_iter(fields))
+        .map(move |(idx, (_, field))| with::value(field, quote! { &self.#idx }, kind))
+}
+
+/// For each visited field, its member and type if it is flattened
+fn flattened_iter(fields: &Fields) -> impl Iterator<Item = Option<(TokenStream, &syn::Type)>> {
+    field_idx_iter(fields)
+        .zip(field_iter(fields))
+        .map(|(idx, (_, field))| flatten::is_flattened(field).then_some((idx, &field.ty)))
+}
+
 fn field_name_idx_iter<'a>(
     ast: &'a DeriveInput,
     fields: &'a syn::Fields,
@@ -153,23 +155,141 @@ fn field_name_idx_iter<'a>(
     })
 }
 
+/// A `&'static [visit_rs::FieldInfo]` describing every declared field
+fn field_infos(fields: &Fields, rename_all_rule: RenameRules) -> TokenStream {
+    let infos = fields.iter().enumerate().map(|(index, field)| {
+        let (ident, name) = match &field.ident {
+            Some(ident) => {
+                let ident = ident.to_string();
+                let renamed = get_field_rename(field, rename_all_rule).unwrap_or(ident.clone());
+                (quote! { Some(#ident) }, quote! { Some(#renamed) })
+            }
+            None => (quote! { None }, quote! { None }),
+        };
+        let type_name = type_name(&field.ty);
+        let skip = is_skipped(field);
+        let flatten = flatten::is_flattened(field);
+
+        let metas = attrs::extract_all_meta(&field.attrs);
+        let metadata_ref = attrs::metadata_ref(&metas);
+
+        quote! {
+            visit_rs::__with_metadata!(visit_rs::FieldInfo {
+                ident: #ident,
+                name: #name,
+                index: #index,
+                type_name: #type_name,
+                skip: #skip,
+                flatten: #flatten,
+            } metadata: #metadata_ref)
+        }
+    });
+
+    quote! {
+        {
+            const FIELDS: &[visit_rs::FieldInfo] = &[#(#infos),*];
+            FIELDS
+        }
+    }
+}
+
+/// Generated code reaches visit-rs through the path `visit_rs`, resolved where the
+/// derive is used. Use `#[visit(crate = "path::to::visit_rs")]` when it is renamed
+/// or re-exported; a crate that re-exports it as `visit_rs` (e.g. in a prelude)
+/// needs no attribute.
+///
+/// Each impl bounds every field type by the trait it visits that field with.
+/// `#[visit(bound = "...")]` replaces those predicates: on the container it
+/// replaces all of them, on a field just that field's. `#[visit(bound(visit =
+/// "...", dyn_visit = "..."))]` does so per family of impls, keyed by `visit`,
+/// `visit_async`, `visit_async_local`, `visit_mut`, `visit_owned`, `build` or
+/// `dyn_visit`. The visitor type parameter is named `__visit_rs__V`.
+///
+/// `#[visit(with = "module")]` on a field visits it through `module::visit`,
+/// `visit_async`, `visit_static`, `visit_static_async`, `visit_mut` and, with
+/// `owned`, `visit_owned` instead of its own impls, with local visits going
+/// through `visit_async`; `#[visit(visit_with = "path::to::fn")]` replaces just
+/// `visit`.
+/// See `visit_rs::with`.
+///
+/// Names follow serde's: `rename`, `rename_all` and, on enums, `rename_all_fields`
+/// are read from `#[visit(...)]` or `#[serde(...)]`, each either for both
+/// directions or split as `rename(serialize = "...", deserialize = "...")`. Fields
+/// and variants are visited under the name they serialize as, and looked up by
+/// that, the name they deserialize from, or any `alias`.
+///
+/// `VisitFieldsOwned` is only derived for a struct marked `#[visit(owned)]`, as
+/// moving its fields out does not compile if it implements `Drop`.
+///
+/// `#[visit(follow_serde)]` on the struct visits the fields serde would serialize:
+/// fields marked `skip` or `skip_serializing`, in `#[visit(...)]` or `#[serde(...)]`,
+/// are left out as with `#[visit(skip)]`, and the named visits (`visit_fields_named`
+/// in every form, `visit_fields_pathed` and `dyn_visit_fields`) pass over a field
+/// whose `skip_serializing_if` predicate holds for its current value.
+///
+/// `#[visit(flatten)]` on a field of a struct that itself derives `VisitFields`
+/// visits that field's fields in its place, like `#[serde(flatten)]`. Concurrent
+/// visits report them under the flattened field's index, and `VisitField` finds
+/// them by name only. The sync visits of such a struct are boxed streams polled in
+/// place, so that the visitor can pass from one field's iterator to the next. The
+/// stream only calls sync impls and never awaits, and its iterator panics should it
+/// ever be pending.
+///
+/// On a `union`, only the impls that never read a value are derived: `StructInfo`,
+/// `VisitFieldsStatic`, `VisitFieldsStaticNamed`, `VisitFieldStatic` and their
+/// async forms.
+///
+/// Malformed or misplaced keys in `#[visit(...)]` are compile errors. Any other key
+/// is kept as metadata (see `visit_rs::metadata`), unless it is a bare key one edit
+/// away from one of the keys above that applies there, which looks like a typo.
 #[proc_macro_derive(VisitFields, attributes(visit))]
 pub fn derive_visit_fields_(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: DeriveInput = syn::parse(input).unwrap();
+    let mut ast = syn::parse_macro_input!(input as DeriveInput);
+    if let Err(e) = keys::check(&ast) {
+        return e.to_compile_error().into();
+    }
+    if let Err(e) = follow_serde::mark_skipped(&mut ast) {
+        return e.to_compile_error().into();
+    }
 
-    let syn::Data::Struct(data) = &ast.data else {
-        let span = match &ast.data {
-            syn::Data::Enum(data) => data.enum_token.span,
-            syn::Data::Union(data) => data.union_token.span,
-            _ => Span::call_site(),
-        };
-        return syn::Error::new(span, "VisitFields can only be derived for structs")
+    let union_data;
+    let (data, is_union) = match &ast.data {
+        syn::Data::Struct(data) => (data, false),
+        // A union's fields are described as a struct's, but only by the impls that
+        // never read a value
+        syn::Data::Union(data) => {
+            union_data = DataStruct {
+                struct_token: syn::Token![struct](data.union_token.span),
+                fields: Fields::Named(data.fields.clone()),
+                semi_token: None,
+            };
+            (&union_data, true)
+        }
+        syn::Data::Enum(data) => {
+            return syn::Error::new(
+                data.enum_token.span,
+                "VisitFields can only be derived for structs and unions",
+            )
             .to_compile_error()
             .into();
+        }
     };
 
     let all_impls = match (|| {
-        Ok::<_, syn::Error>([
+        with::check_fields(&data.fields)?;
+        flatten::check_fields(&data.fields)?;
+        if is_union {
+            return Ok(vec![
+                derive_struct_info(&ast, data)?,
+                derive_visit_fields_static(&ast, data)?,
+                derive_visit_fields_static_async(&ast, data)?,
+                derive_visit_fields_static_named(&ast, data)?,
+                derive_visit_fields_static_named_async(&ast, data)?,
+                derive_visit_field_static(&ast, data)?,
+                derive_visit_field_static_async(&ast, data)?,
+            ]);
+        }
+        Ok::<_, syn::Error>(vec![
             derive_struct_info(&ast, data)?,
             derive_visit_fields(&ast, data)?,
             derive_visit_fields_covered(&ast, data)?,
@@ -177,82 +297,110 @@ pub fn derive_visit_fields_(input: proc_macro::TokenStream) -> proc_macro::Token
             derive_visit_fields_covered_async(&ast, data)?,
             derive_visit_fields_named(&ast, data)?,
             derive_visit_fields_named_async(&ast, data)?,
+            derive_visit_fields_async_local(&ast, data)?,
+            derive_visit_fields_named_async_local(&ast, data)?,
+            derive_visit_fields_pathed(&ast, data)?,
+            derive_visit_fields_concurrent(&ast, data)?,
+            derive_visit_fields_named_concurrent(&ast, data)?,
+            derive_par_visit_fields(&ast, data)?,
+            derive_par_visit_fields_named(&ast, data)?,
+            derive_visit_fields_mut(&ast, data)?,
+            derive_visit_fields_named_mut(&ast, data)?,
+            derive_visit_fields_owned(&ast, data)?,
+            derive_from_fields(&ast, data)?,
+            derive_dyn_visit_fields(&ast, data)?,
             derive_visit_fields_static(&ast, data)?,
             derive_visit_fields_static_async(&ast, data)?,
             derive_visit_fields_static_named(&ast, data)?,
             derive_visit_fields_static_named_async(&ast, data)?,
+            derive_visit_field(&ast, data)?,
+            derive_visit_field_static(&ast, data)?,
+            derive_visit_field_async(&ast, data)?,
+            derive_visit_field_static_async(&ast, data)?,
         ])
     })() {
         Ok(a) => a,
         Err(e) => return e.to_compile_error().into(),
     };
 
-    // panic!(
-    //     "{}",
-    proc_macro::TokenStream::from(quote! {
-        #(#all_impls)*
-    })
-    // )
+    match wrap_impls(&ast, quote! { #(#all_impls)* }) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
 fn derive_struct_info(ast: &DeriveInput, data: &DataStruct) -> Result<TokenStream, syn::Error> {
     let ident = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    // Flattened fields count as many fields as they visit
+    let mut generics = ast.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    let plain_count = flattened_iter(&data.fields).filter(Option::is_none).count();
+    let flattened_counts: Vec<_> = flattened_iter(&data.fields)
+        .flatten()
+        .map(|(_, ty)| {
+            predicates.push(parse_quote! { #ty: visit_rs::StructInfo });
+            quote! { <#ty as visit_rs::StructInfo>::DATA.field_count }
+        })
+        .collect();
+    let field_count = quote! { #plain_count #(+ #flattened_counts)* };
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
 
     let named_fields = matches!(data.fields, Fields::Named(_));
-    let field_count = field_iter(&data.fields).count();
 
     let name = get_rename_attribute(ast).unwrap_or_else(|| ident.to_string());
 
     // Extract struct-level metadata (both visit and serde attributes)
     let struct_meta = attrs::extract_all_meta(&ast.attrs);
 
-    let struct_meta_ref = if !struct_meta.is_empty() {
-        let count = struct_meta.len();
-        quote! {
-            if cfg!(feature = "meta") {
-                const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#struct_meta),*];
-                &META
-            } else {
-                &[]
-            }
-        }
-    } else {
-        quote! { &[] }
-    };
+    let struct_meta_ref = attrs::metadata_ref(&struct_meta);
+
+    let fields = field_infos(&data.fields, get_rename_all_attribute(ast));
 
     Ok(quote! {
         impl #impl_generics visit_rs::StructInfo for #ident #ty_generics #where_clause {
-            const DATA: visit_rs::StructInfoData = visit_rs::StructInfoData {
+            const DATA: visit_rs::StructInfoData = visit_rs::__with_metadata!(visit_rs::StructInfoData {
                 name: #name,
                 named_fields: #named_fields,
                 field_count: #field_count,
-                #[cfg(feature = "meta")]
-                metadata: #struct_meta_ref,
-            };
+                fields: Self::FIELDS,
+                variant: None,
+            } metadata: #struct_meta_ref);
+            const FIELDS: &'static [visit_rs::FieldInfo] = #fields;
         }
     })
 }
 
 fn derive_visit_fields(ast: &DeriveInput, data: &DataStruct) -> Result<TokenStream, syn::Error> {
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFields },
         &syn::parse_quote! { visit_rs::Visit },
         None,
         false,
         false,
-    );
+    )?;
 
-    let visit_fields_impl = field_idx_iter(&data.fields).enumerate().map(|(num, idx)| {
-        quote! {
-            #num => {
-                pos += 1;
-                Some(visit_rs::Visit::visit(&self.#idx, visitor))
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_impl = field_value_iter(&data.fields, BoundKind::Visit)
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, (value, flattened))| {
+            if let Some((idx, _)) = flattened {
+                let iter = quote! { visit_rs::VisitFields::visit_fields(&self.#idx, #visitor) };
+                return flatten::arm(num, iter);
             }
-        }
-    });
+            quote! {
+                #num => {
+                    pos += 1;
+                    Some(visit_rs::Visit::visit(#value, visitor))
+                }
+            }
+        });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_impl);
 
     Ok(quote! {
         #impl_t {
@@ -260,13 +408,7 @@ fn derive_visit_fields(ast: &DeriveInput, data: &DataStruct) -> Result<TokenStre
                 &'__visit_rs__a self,
                 visitor: &'__visit_rs__a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> {
-                std::iter::from_fn({
-                    let mut pos = 0;
-                    move || match pos {
-                        #(#visit_fields_impl)*
-                        _ => None,
-                    }
-                })
+                #iter
             }
         }
     })
@@ -277,23 +419,35 @@ fn derive_visit_fields_covered(
     data: &DataStruct,
 ) -> Result<TokenStream, syn::Error> {
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsCovered },
         &syn::parse_quote! { visit_rs::Visit },
         Some(&syn::parse_quote! { visit_rs::Covered }),
         false,
         false,
-    );
+    )?;
 
-    let visit_fields_impl = field_idx_iter(&data.fields).enumerate().map(|(num, idx)| {
-        quote! {
-            #num => {
-                pos += 1;
-                Some(visit_rs::Visit::visit(&visit_rs::Covered(&self.#idx), visitor))
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_impl = field_value_iter(&data.fields, BoundKind::Visit)
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, (value, flattened))| {
+            if let Some((idx, _)) = flattened {
+                let iter = quote! {
+                    visit_rs::VisitFieldsCovered::visit_fields_covered(&self.#idx, #visitor)
+                };
+                return flatten::arm(num, iter);
             }
-        }
-    });
+            quote! {
+                #num => {
+                    pos += 1;
+                    Some(visit_rs::Visit::visit(&visit_rs::Covered(#value), visitor))
+                }
+            }
+        });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_impl);
 
     Ok(quote! {
         #impl_t {
@@ -301,13 +455,7 @@ fn derive_visit_fields_covered(
                 &'__visit_rs__a self,
                 visitor: &'__visit_rs__a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> {
-                std::iter::from_fn({
-                    let mut pos = 0;
-                    move || match pos {
-                        #(#visit_fields_impl)*
-                        _ => None,
-                    }
-                })
+                #iter
             }
         }
     })
@@ -318,20 +466,25 @@ fn derive_visit_fields_async(
     data: &DataStruct,
 ) -> Result<TokenStream, syn::Error> {
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsAsync },
         &syn::parse_quote! { visit_rs::VisitAsync },
         None,
         true,
         false,
-    );
-
-    let visit_fields_impl = field_idx_iter(&data.fields).map(|idx| {
-        quote! {
-            yield visit_rs::VisitAsync::visit_async(&self.#idx, visitor).await;
-        }
-    });
+    )?;
+
+    let visit_fields_impl = field_value_iter(&data.fields, BoundKind::VisitAsync)
+        .zip(flattened_iter(&data.fields))
+        .map(|(value, flattened)| match flattened {
+            Some((idx, _)) => flatten::yield_stream(quote! {
+                visit_rs::VisitFieldsAsync::visit_fields_async(&self.#idx, &mut *visitor)
+            }),
+            None => {
+                yield_visit(quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await })
+            }
+        });
 
     Ok(quote! {
         #impl_t {
@@ -355,25 +508,74 @@ fn derive_visit_fields_async(
     })
 }
 
+fn derive_visit_fields_async_local(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsAsyncLocal },
+        &syn::parse_quote! { visit_rs::VisitAsyncLocal },
+        None,
+        false,
+        false,
+    )?;
+
+    let visit_fields_impl = field_value_iter(&data.fields, BoundKind::VisitAsyncLocal)
+        .zip(flattened_iter(&data.fields))
+        .map(|(value, flattened)| match flattened {
+            Some((idx, _)) => flatten::yield_stream(quote! {
+                visit_rs::VisitFieldsAsyncLocal::visit_fields_async_local(&self.#idx, &mut *visitor)
+            }),
+            None => yield_visit(
+                quote! { visit_rs::VisitAsyncLocal::visit_async_local(#value, visitor).await },
+            ),
+        });
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_async_local<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
+            {
+                visit_rs::lib::async_stream::stream! {
+                    #(#visit_fields_impl)*
+                    #[allow(unreachable_code)]
+                    if false {
+                        yield unreachable!() as <__visit_rs__V as visit_rs::Visitor>::Result
+                    }
+                }
+            }
+        }
+    })
+}
+
 fn derive_visit_fields_covered_async(
     ast: &DeriveInput,
     data: &DataStruct,
 ) -> Result<TokenStream, syn::Error> {
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsCoveredAsync },
         &syn::parse_quote! { visit_rs::VisitAsync },
         Some(&syn::parse_quote! { visit_rs::Covered }),
         true,
         false,
-    );
-
-    let visit_fields_impl = field_idx_iter(&data.fields).map(|idx| {
-        quote! {
-            yield visit_rs::VisitAsync::visit_async(&visit_rs::Covered(&self.#idx), visitor).await;
-        }
-    });
+    )?;
+
+    let visit_fields_impl = field_value_iter(&data.fields, BoundKind::VisitAsync)
+        .zip(flattened_iter(&data.fields))
+        .map(|(value, flattened)| match flattened {
+            Some((idx, _)) => flatten::yield_stream(quote! {
+                visit_rs::VisitFieldsCoveredAsync::visit_fields_covered_async(&self.#idx, &mut *visitor)
+            }),
+            None => yield_visit(
+                quote! { visit_rs::VisitAsync::visit_async(&visit_rs::Covered(#value), visitor).await },
+            ),
+        });
 
     Ok(quote! {
         #impl_t {
@@ -404,54 +606,60 @@ fn derive_visit_fields_named(
     let _ident = &ast.ident;
 
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsNamed },
         &syn::parse_quote! { visit_rs::Visit },
         Some(&syn::parse_quote! { visit_rs::Named }),
         false,
         false,
-    );
+    )?;
 
     // Extract field metadata
     let field_metas: Vec<_> = field_iter(&data.fields)
         .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
         .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::Visit).collect();
 
-    let visit_fields_named_impl =
-        field_name_idx_iter(ast, &data.fields)
-            .enumerate()
-            .map(|(num, (name, idx))| {
-                let metadata_ref = if !field_metas[num].is_empty() {
-                    let metas = &field_metas[num];
-                    let count = metas.len();
-                    quote! {
-                        if cfg!(feature = "meta") {
-                            const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                            &META
-                        } else {
-                            &[]
-                        }
-                    }
-                } else {
-                    quote! { &[] }
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_named_impl = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, (((name, idx), (_, field)), flattened))| {
+            if let Some((idx, _)) = flattened {
+                let iter = quote! {
+                    visit_rs::VisitFieldsNamed::visit_fields_named(&self.#idx, #visitor)
                 };
+                return Ok(flatten::arm(num, iter));
+            }
+            let value = &values[num];
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
 
-                quote! {
-                    #num => {
-                        pos += 1;
-                        Some({
-                            let named = visit_rs::Named {
-                                name: #name,
-                                #[cfg(feature = "meta")]
-                                metadata: #metadata_ref,
-                                value: &self.#idx,
-                            };
-                            visit_rs::Visit::visit(&named, visitor)
-                        })
-                    }
+            let visit = quote! {
+                Some({
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: #value,
+                    } metadata: #metadata_ref);
+                    visit_rs::Visit::visit(&named, visitor)
+                })
+            };
+            let visit = follow_serde::guard(ast, field, &idx, visit, quote! { continue })?;
+            Ok(quote! {
+                #num => {
+                    pos += 1;
+                    #visit
                 }
-            });
+            })
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let iter = flatten::sync_iter_skipping(
+        &data.fields,
+        visit_fields_named_impl.into_iter(),
+        follow_serde::is_enabled(ast),
+    );
 
     Ok(quote! {
         #impl_t {
@@ -459,13 +667,7 @@ fn derive_visit_fields_named(
                 &'__visit_rs__a self,
                 visitor: &'__visit_rs__a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
-                std::iter::from_fn({
-                    let mut pos = 0;
-                    move || match pos {
-                        #(#visit_fields_named_impl)*
-                        _ => None,
-                    }
-                })
+                #iter
             }
         }
     })
@@ -478,51 +680,50 @@ fn derive_visit_fields_named_async(
     let _ident = &ast.ident;
 
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsNamedAsync },
         &syn::parse_quote! { visit_rs::VisitAsync },
         Some(&syn::parse_quote! { visit_rs::Named }),
         true,
         false,
-    );
+    )?;
+
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await });
 
     // Extract field metadata
     let field_metas: Vec<_> = field_iter(&data.fields)
         .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
         .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::VisitAsync).collect();
 
     let visit_fields_named_impl =
         field_name_idx_iter(ast, &data.fields)
+            .zip(field_iter(&data.fields))
+            .zip(flattened_iter(&data.fields))
             .enumerate()
-            .map(|(num, (name, idx))| {
-                let metadata_ref = if !field_metas[num].is_empty() {
-                    let metas = &field_metas[num];
-                    let count = metas.len();
-                    quote! {
-                        if cfg!(feature = "meta") {
-                            const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                            &META
-                        } else {
-                            &[]
-                        }
-                    }
-                } else {
-                    quote! { &[] }
-                };
+            .map(|(num, (((name, idx), (_, field)), flattened))| {
+                if let Some((idx, _)) = flattened {
+                    return Ok(flatten::yield_stream(quote! {
+                        visit_rs::VisitFieldsNamedAsync::visit_fields_named_async(&self.#idx, &mut *visitor)
+                    }));
+                }
+                let value = &values[num];
+                let metadata_ref = attrs::metadata_ref(&field_metas[num]);
 
-                quote! {
+                let visit = quote! {
                     {
-                        let named = visit_rs::Named {
+                        let named = visit_rs::__with_metadata!(visit_rs::Named {
                             name: #name,
-                            #[cfg(feature = "meta")]
-                            metadata: #metadata_ref,
-                            value: &self.#idx,
-                        };
-                        yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                            value: #value,
+                        } metadata: #metadata_ref);
+                        #yield_named
                     }
-                }
-            });
+                };
+                follow_serde::guard(ast, field, &idx, visit, quote! {})
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
 
     Ok(quote! {
         #impl_t {
@@ -546,83 +747,67 @@ fn derive_visit_fields_named_async(
     })
 }
 
-fn derive_visit_fields_static(
+fn derive_visit_fields_named_async_local(
     ast: &DeriveInput,
     data: &DataStruct,
 ) -> Result<TokenStream, syn::Error> {
+    let _ident = &ast.ident;
+
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
-        &syn::parse_quote! { visit_rs::VisitFieldsStatic },
-        &syn::parse_quote! { visit_rs::Visit },
-        None,
+        &syn::parse_quote! { visit_rs::VisitFieldsNamedAsyncLocal },
+        &syn::parse_quote! { visit_rs::VisitAsyncLocal },
+        Some(&syn::parse_quote! { visit_rs::Named }),
         false,
-        true,
-    );
+        false,
+    )?;
 
-    let field_types: Vec<_> = field_iter(&data.fields)
-        .map(|(_, field)| &field.ty)
-        .collect();
-    let visit_fields_impl = field_types.iter().enumerate().map(|(num, ty)| {
-        quote! {
-            #num => {
-                pos += 1;
-                Some(visit_rs::Visit::visit(&visit_rs::Static::<#ty>::new(), visitor))
-            }
-        }
-    });
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsyncLocal::visit_async_local(&named, visitor).await });
 
-    Ok(quote! {
-        #impl_t {
-            fn visit_fields_static<'__visit_rs__a>(
-                visitor: &'__visit_rs__a mut __visit_rs__V
-            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
-                std::iter::from_fn({
-                    let mut pos = 0;
-                    move || match pos {
-                        #(#visit_fields_impl)*
-                        _ => None,
-                    }
-                })
-            }
-        }
-    })
-}
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::VisitAsyncLocal).collect();
 
-fn derive_visit_fields_static_async(
-    ast: &DeriveInput,
-    data: &DataStruct,
-) -> Result<TokenStream, syn::Error> {
-    let impl_t = make_impl(
-        &ast,
-        &data.fields,
-        &syn::parse_quote! { visit_rs::VisitFieldsStaticAsync },
-        &syn::parse_quote! { visit_rs::VisitAsync },
-        None,
-        true,
-        true,
-    );
+    let visit_fields_named_impl =
+        field_name_idx_iter(ast, &data.fields)
+            .zip(field_iter(&data.fields))
+            .zip(flattened_iter(&data.fields))
+            .enumerate()
+            .map(|(num, (((name, idx), (_, field)), flattened))| {
+                if flattened.is_some() {
+                    return Ok(flatten::yield_stream(quote! {
+                        visit_rs::VisitFieldsNamedAsyncLocal::visit_fields_named_async_local(&self.#idx, &mut *visitor)
+                    }));
+                }
+                let value = &values[num];
+                let metadata_ref = attrs::metadata_ref(&field_metas[num]);
 
-    let field_types: Vec<_> = field_iter(&data.fields)
-        .map(|(_, field)| &field.ty)
-        .collect();
-    let visit_fields_impl = field_types.iter().map(|ty| {
-        quote! {
-            yield visit_rs::VisitAsync::visit_async(&visit_rs::Static::<#ty>::new(), visitor).await;
-        }
-    });
+                let visit = quote! {
+                    {
+                        let named = visit_rs::__with_metadata!(visit_rs::Named {
+                            name: #name,
+                            value: #value,
+                        } metadata: #metadata_ref);
+                        #yield_named
+                    }
+                };
+                follow_serde::guard(ast, field, &idx, visit, quote! {})
+            })
+            .collect::<Result<Vec<_>, syn::Error>>()?;
 
     Ok(quote! {
         #impl_t {
-            fn visit_fields_static_async<'__visit_rs__a>(
+            fn visit_fields_named_async_local<'__visit_rs__a>(
+                &'__visit_rs__a self,
                 visitor: &'__visit_rs__a mut __visit_rs__V,
-            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + Send + '__visit_rs__a
-            where
-                __visit_rs__V: Send,
-                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
             {
                 visit_rs::lib::async_stream::stream! {
-                    #(#visit_fields_impl)*
+                    #(#visit_fields_named_impl)*
                     #[allow(unreachable_code)]
                     if false {
                         yield unreachable!() as <__visit_rs__V as visit_rs::Visitor>::Result
@@ -633,21 +818,973 @@ fn derive_visit_fields_static_async(
     })
 }
 
-fn derive_visit_fields_static_named(
+fn derive_visit_fields_pathed(
     ast: &DeriveInput,
     data: &DataStruct,
 ) -> Result<TokenStream, syn::Error> {
-    let _ident = &ast.ident;
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(syn::parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::path::PathVisitor });
+    push_field_bounds(
+        predicates,
+        ast,
+        field_iter(&data.fields).map(|(_, field)| field),
+        BoundKind::Visit,
+        |field| {
+            let ty = &field.ty;
+            if flatten::is_flattened(field) {
+                return vec![
+                    parse_quote! { #ty: visit_rs::path::VisitFieldsPathed<__visit_rs__V> },
+                ];
+            }
+            let wrapper = quote! { visit_rs::Named };
+            if let Some(bound) = with::bound(field, BoundKind::Visit, false, Some(wrapper)) {
+                return bound;
+            }
+            vec![parse_quote! {
+                for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::Visit<__visit_rs__V>
+            }]
+        },
+    )?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let rename_all_rule = get_rename_all_attribute(ast);
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::Visit).collect();
+
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_pathed_impl = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .enumerate()
+        .map(|(num, ((name, idx), (index, field)))| {
+            if flatten::is_flattened(field) {
+                let iter = quote! {
+                    visit_rs::path::VisitFieldsPathed::visit_fields_pathed(&self.#idx, #visitor)
+                };
+                return Ok(flatten::arm(num, iter));
+            }
+            let value = &values[num];
+            let segment = match get_field_rename(field, rename_all_rule) {
+                Some(renamed) => quote! { visit_rs::path::PathSegment::Field(#renamed) },
+                None => quote! { visit_rs::path::PathSegment::TupleIndex(#index) },
+            };
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            let visit = quote! {
+                Some({
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: #value,
+                    } metadata: #metadata_ref);
+                    visit_rs::path::PathVisitor::visit_at(visitor, #segment, &named)
+                })
+            };
+            let visit = follow_serde::guard(ast, field, &idx, visit, quote! { continue })?;
+            Ok(quote! {
+                #num => {
+                    pos += 1;
+                    #visit
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let iter = flatten::sync_iter_skipping(
+        &data.fields,
+        visit_fields_pathed_impl.into_iter(),
+        follow_serde::is_enabled(ast),
+    );
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::path::VisitFieldsPathed<__visit_rs__V> for #ident #ty_generics #where_clause {
+            fn visit_fields_pathed<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                visitor: &'__visit_rs__a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
+                #iter
+            }
+        }
+    })
+}
+
+/// The impl header for a concurrent visit, which needs `V: ForkVisitor` and `Sync`
+/// fields on top of the bounds of the async visit
+fn make_concurrent_impl(
+    ast: &DeriveInput,
+    fields: &Fields,
+    trait_path_fields: &Path,
+    named: bool,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(syn::parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::ForkVisitor });
+    push_field_bounds(predicates, ast, fields, BoundKind::VisitAsync, |field| {
+        let ty = &field.ty;
+        let mut inferred: Vec<WherePredicate> =
+            vec![parse_quote! { for<'__visit_rs__sync> #ty: Sync }];
+        if is_skipped(field) {
+            return inferred;
+        }
+        if flatten::is_flattened(field) {
+            inferred.push(parse_quote! { #ty: #trait_path_fields<__visit_rs__V> });
+            return inferred;
+        }
+        let wrapper = named.then(|| quote! { visit_rs::Named });
+        if let Some(bound) = with::bound(field, BoundKind::VisitAsync, false, wrapper) {
+            inferred.extend(bound);
+            return inferred;
+        }
+        inferred.push(if named {
+            parse_quote! {
+                for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, #ty>: visit_rs::VisitAsync<__visit_rs__V>
+            }
+        } else {
+            parse_quote! { #ty: visit_rs::VisitAsync<__visit_rs__V> }
+        });
+        inferred
+    })?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #trait_path_fields<__visit_rs__V> for #ident #ty_generics #where_clause
+    })
+}
+
+/// Drive `visit`, which maps each position `pos` from the iterator `positions`,
+/// with a fork of the visitor, to an `(index, result)` pair, through
+/// `buffer_unordered`, at least one at a time
+fn concurrent_stream(positions: TokenStream, visit: TokenStream) -> TokenStream {
+    quote! {
+        let forks: Vec<(usize, __visit_rs__V)> = (#positions)
+            .map(|pos| (pos, visit_rs::ForkVisitor::fork(visitor)))
+            .collect();
+        visit_rs::lib::futures::StreamExt::buffer_unordered(
+            visit_rs::lib::futures::StreamExt::map(
+                visit_rs::lib::futures::stream::iter(forks),
+                move |(pos, mut visitor)| async move {
+                    let visitor = &mut visitor;
+                    #[allow(unreachable_code)]
+                    #visit
+                },
+            ),
+            limit.max(1),
+        )
+    }
+}
+
+fn derive_visit_fields_concurrent(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_concurrent_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsConcurrent },
+        false,
+    )?;
+
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::VisitAsync).collect();
+    let idxs: Vec<_> = field_idx_iter(&data.fields).collect();
+    let indexes: Vec<_> = field_iter(&data.fields).map(|(index, _)| index).collect();
+
+    // Each flattened field is visited concurrently in turn, after the fields before it
+    let streams = flatten::segments(&data.fields)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Fields(nums) => {
+                let visit_arms = nums.iter().enumerate().map(|(pos, &num)| {
+                    let (value, index) = (&values[num], indexes[num]);
+                    quote! {
+                        #pos => (#index, visit_rs::VisitAsync::visit_async(#value, visitor).await),
+                    }
+                });
+                let count = nums.len();
+                let stream = concurrent_stream(
+                    quote! { 0..#count },
+                    quote! {
+                        match pos {
+                            #(#visit_arms)*
+                            _ => unreachable!(),
+                        }
+                    },
+                );
+                quote! { { #stream } }
+            }
+            Segment::Flattened(num) => {
+                let (idx, index) = (&idxs[num], indexes[num]);
+                quote! {
+                    visit_rs::lib::futures::StreamExt::map(
+                        visit_rs::VisitFieldsConcurrent::visit_fields_concurrent(&self.#idx, visitor, limit),
+                        |(_, res)| (#index, res),
+                    )
+                }
+            }
+        });
+    let stream = flatten::chain(quote! { visit_rs::lib::futures::StreamExt::chain }, streams);
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_concurrent<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                visitor: &__visit_rs__V,
+                limit: usize,
+            ) -> impl visit_rs::lib::futures::Stream<Item = (usize, <__visit_rs__V as visit_rs::Visitor>::Result)> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send + '__visit_rs__a,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                #stream
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_named_concurrent(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_concurrent_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsNamedConcurrent },
+        true,
+    )?;
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::VisitAsync).collect();
+
+    let idxs: Vec<_> = field_idx_iter(&data.fields).collect();
+    let indexes: Vec<_> = field_iter(&data.fields).map(|(index, _)| index).collect();
+
+    let visit_arms: Vec<_> = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .enumerate()
+        .map(|(num, ((name, _), (index, _)))| {
+            let value = &values[num];
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            quote! {
+                {
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: #value,
+                    } metadata: #metadata_ref);
+                    (#index, visit_rs::VisitAsync::visit_async(&named, visitor).await)
+                }
+            }
+        })
+        .collect();
+
+    let fields: Vec<_> = field_iter(&data.fields).map(|(_, field)| field).collect();
+
+    // Each flattened field is visited concurrently in turn, after the fields before it
+    let streams = flatten::segments(&data.fields)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Fields(nums) => {
+                let positions = follow_serde::positions(
+                    ast,
+                    nums.iter().map(|&num| (fields[num], &idxs[num])),
+                )?;
+                let visit_arms = nums.iter().enumerate().map(|(pos, &num)| {
+                    let visit = &visit_arms[num];
+                    quote! { #pos => #visit, }
+                });
+                let stream = concurrent_stream(
+                    positions,
+                    quote! {
+                        match pos {
+                            #(#visit_arms)*
+                            _ => unreachable!(),
+                        }
+                    },
+                );
+                Ok(quote! { { #stream } })
+            }
+            Segment::Flattened(num) => {
+                let (idx, index) = (&idxs[num], indexes[num]);
+                Ok(quote! {
+                    visit_rs::lib::futures::StreamExt::map(
+                        visit_rs::VisitFieldsNamedConcurrent::visit_fields_named_concurrent(&self.#idx, visitor, limit),
+                        |(_, res)| (#index, res),
+                    )
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    let stream = flatten::chain(quote! { visit_rs::lib::futures::StreamExt::chain }, streams);
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_named_concurrent<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                visitor: &__visit_rs__V,
+                limit: usize,
+            ) -> impl visit_rs::lib::futures::Stream<Item = (usize, <__visit_rs__V as visit_rs::Visitor>::Result)> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send + '__visit_rs__a,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                #stream
+            }
+        }
+    })
+}
+
+fn derive_par_visit_fields(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::rayon::ParVisitFields },
+        &syn::parse_quote! { visit_rs::Visit },
+        None,
+        true,
+        false,
+    )?;
+
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::Visit).collect();
+    let idxs: Vec<_> = field_idx_iter(&data.fields).collect();
+
+    // Chained in field order, so that the results stay indexed by position
+    let iters = flatten::segments(&data.fields)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Fields(nums) => {
+                let field_count = nums.len();
+                let par_visit_fields_impl = nums.iter().enumerate().map(|(pos, &num)| {
+                    let value = &values[num];
+                    quote! {
+                        #pos => visit_rs::Visit::visit(#value, visitor),
+                    }
+                });
+                quote! {
+                    visit_rs::lib::rayon::iter::ParallelIterator::map_with(
+                        visit_rs::lib::rayon::iter::IntoParallelIterator::into_par_iter(0..#field_count),
+                        visitor.clone(),
+                        move |visitor, pos| match pos {
+                            #(#par_visit_fields_impl)*
+                            _ => unreachable!(),
+                        },
+                    )
+                }
+            }
+            Segment::Flattened(num) => {
+                let idx = &idxs[num];
+                quote! { visit_rs::rayon::ParVisitFields::par_visit_fields(&self.#idx, visitor) }
+            }
+        });
+    let iter = flatten::chain(
+        quote! { visit_rs::lib::rayon::iter::ParallelIterator::chain },
+        iters,
+    );
+
+    Ok(quote! {
+        visit_rs::__if_rayon! {
+            #impl_t {
+                fn par_visit_fields<'__visit_rs__a>(
+                    &'__visit_rs__a self,
+                    visitor: &__visit_rs__V,
+                ) -> impl visit_rs::lib::rayon::iter::IndexedParallelIterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
+                where
+                    __visit_rs__V: Clone + Send + '__visit_rs__a,
+                    <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+                {
+                    #iter
+                }
+            }
+        }
+    })
+}
+
+fn derive_par_visit_fields_named(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::rayon::ParVisitFieldsNamed },
+        &syn::parse_quote! { visit_rs::Visit },
+        Some(&syn::parse_quote! { visit_rs::Named }),
+        true,
+        false,
+    )?;
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+    let values: Vec<_> = field_value_iter(&data.fields, BoundKind::Visit).collect();
+
+    let idxs: Vec<_> = field_idx_iter(&data.fields).collect();
+
+    let par_visit_fields_named_impl: Vec<_> = field_name_idx_iter(ast, &data.fields)
+        .enumerate()
+        .map(|(num, (name, _))| {
+            let value = &values[num];
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            quote! {
+                {
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: #value,
+                    } metadata: #metadata_ref);
+                    visit_rs::Visit::visit(&named, visitor)
+                }
+            }
+        })
+        .collect();
+
+    let fields: Vec<_> = field_iter(&data.fields).map(|(_, field)| field).collect();
+
+    // Chained in field order, so that the results stay indexed by position
+    let iters = flatten::segments(&data.fields)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Fields(nums) => {
+                let positions = follow_serde::positions(
+                    ast,
+                    nums.iter().map(|&num| (fields[num], &idxs[num])),
+                )?;
+                let par_visit_fields_named_impl = nums.iter().enumerate().map(|(pos, &num)| {
+                    let visit = &par_visit_fields_named_impl[num];
+                    quote! { #pos => #visit, }
+                });
+                Ok(quote! {
+                    visit_rs::lib::rayon::iter::ParallelIterator::map_with(
+                        visit_rs::lib::rayon::iter::IntoParallelIterator::into_par_iter(
+                            (#positions).collect::<Vec<usize>>(),
+                        ),
+                        visitor.clone(),
+                        move |visitor, pos| match pos {
+                            #(#par_visit_fields_named_impl)*
+                            _ => unreachable!(),
+                        },
+                    )
+                })
+            }
+            Segment::Flattened(num) => {
+                let idx = &idxs[num];
+                Ok(quote! {
+                    visit_rs::rayon::ParVisitFieldsNamed::par_visit_fields_named(&self.#idx, visitor)
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+    let iter = flatten::chain(
+        quote! { visit_rs::lib::rayon::iter::ParallelIterator::chain },
+        iters,
+    );
+
+    Ok(quote! {
+        visit_rs::__if_rayon! {
+            #impl_t {
+                fn par_visit_fields_named<'__visit_rs__a>(
+                    &'__visit_rs__a self,
+                    visitor: &__visit_rs__V,
+                ) -> impl visit_rs::lib::rayon::iter::IndexedParallelIterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
+                where
+                    __visit_rs__V: Clone + Send + '__visit_rs__a,
+                    <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+                {
+                    #iter
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_mut(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsMut },
+        &syn::parse_quote! { visit_rs::VisitMut },
+        None,
+        false,
+        false,
+    )?;
+
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_impl = field_idx_iter(&data.fields)
+        .zip(field_iter(&data.fields))
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, ((idx, (_, field)), flattened))| {
+            if flattened.is_some() {
+                let iter = quote! {
+                    visit_rs::VisitFieldsMut::visit_fields_mut(&mut self.#idx, #visitor)
+                };
+                return flatten::arm(num, iter);
+            }
+            let value = with::value(field, quote! { &mut self.#idx }, BoundKind::VisitMut);
+            quote! {
+                #num => {
+                    pos += 1;
+                    Some(visit_rs::VisitMut::visit_mut(#value, visitor))
+                }
+            }
+        });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_impl);
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_mut<'__visit_rs__a>(
+                &'__visit_rs__a mut self,
+                visitor: &'__visit_rs__a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
+                #iter
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_named_mut(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsNamedMut },
+        &syn::parse_quote! { visit_rs::VisitMut },
+        Some(&syn::parse_quote! { visit_rs::NamedMut }),
+        false,
+        false,
+    )?;
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_named_impl = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, (((name, idx), (_, field)), flattened))| {
+            if flattened.is_some() {
+                let iter = quote! {
+                    visit_rs::VisitFieldsNamedMut::visit_fields_named_mut(&mut self.#idx, #visitor)
+                };
+                return Ok(flatten::arm(num, iter));
+            }
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+            let value = with::value(field, quote! { &mut self.#idx }, BoundKind::VisitMut);
+
+            let visit = quote! {
+                Some({
+                    let mut named = visit_rs::__with_metadata!(visit_rs::NamedMut {
+                        name: #name,
+                        value: #value,
+                    } metadata: #metadata_ref);
+                    visit_rs::VisitMut::visit_mut(&mut named, visitor)
+                })
+            };
+            let visit = follow_serde::guard(ast, field, &idx, visit, quote! { continue })?;
+            Ok(quote! {
+                #num => {
+                    pos += 1;
+                    #visit
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    let iter = flatten::sync_iter_skipping(
+        &data.fields,
+        visit_fields_named_impl.into_iter(),
+        follow_serde::is_enabled(ast),
+    );
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_named_mut<'__visit_rs__a>(
+                &'__visit_rs__a mut self,
+                visitor: &'__visit_rs__a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
+                #iter
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_owned(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    // Moving fields out of `self` is an error for types that implement `Drop`, which
+    // a derive cannot see, so the impl is opt-in
+    if !has_visit_flag(&ast.attrs, "owned") {
+        return Ok(TokenStream::new());
+    }
+
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsOwned },
+        &syn::parse_quote! { visit_rs::VisitOwned },
+        None,
+        false,
+        false,
+    )?;
+
+    let field_vars: Vec<_> = (0..field_iter(&data.fields).count())
+        .map(|num| Ident::new(&format!("__visit_rs__f{}", num), Span::call_site()))
+        .collect();
+    let field_idxs = field_idx_iter(&data.fields);
+
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_impl = field_vars
+        .iter()
+        .zip(field_iter(&data.fields))
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, ((var, (_, field)), flattened))| {
+        if flattened.is_some() {
+            let iter = quote! {
+                #var
+                    .take()
+                    .map(|value| visit_rs::VisitFieldsOwned::visit_fields_owned(value, #visitor))
+                    .into_iter()
+                    .flatten()
+            };
+            return flatten::arm(num, iter);
+        }
+        let value = with::value(field, quote! { value }, BoundKind::VisitOwned);
+        quote! {
+            #num => {
+                pos += 1;
+                #var.take().map(|value| visit_rs::VisitOwned::visit_owned(#value, visitor))
+            }
+        }
+    });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_impl);
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_owned<'__visit_rs__a>(
+                self,
+                visitor: &'__visit_rs__a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a
+            where
+                Self: '__visit_rs__a,
+            {
+                let Self { #(#field_idxs: #field_vars,)* .. } = self;
+                #(let mut #field_vars = Some(#field_vars);)*
+                #iter
+            }
+        }
+    })
+}
+
+fn derive_from_fields(ast: &DeriveInput, data: &DataStruct) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    let mut generics = ast.generics.clone();
+    generics.params.push(syn::parse_quote! { __visit_rs__V });
+    let predicates = &mut generics.make_where_clause().predicates;
+    predicates.push(syn::parse_quote! { __visit_rs__V: visit_rs::Source });
+
+    push_field_bounds(predicates, ast, &data.fields, BoundKind::Build, |field| {
+        let ty = &field.ty;
+        // Skipped fields are not requested from the source
+        if is_skipped(field) {
+            return vec![parse_quote! { for<'__visit_rs__default> #ty: Default }];
+        }
+        if flatten::is_flattened(field) {
+            return vec![parse_quote! { #ty: visit_rs::FromFields<__visit_rs__V> }];
+        }
+        vec![parse_quote! {
+            for<'__visit_rs__named> visit_rs::Named<'__visit_rs__named, visit_rs::Static<#ty>>:
+                visit_rs::Build<__visit_rs__V, Output = #ty>
+        }]
+    })?;
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+
+    let mut built_fields: Vec<_> = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .enumerate()
+        .map(|(num, ((name, idx), (index, field)))| {
+            if flatten::is_flattened(field) {
+                return (
+                    index,
+                    quote! { #idx: visit_rs::FromFields::from_fields(source)? },
+                );
+            }
+            let ty = &field.ty;
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            let built = quote! {
+                #idx: {
+                    let named = visit_rs::__with_metadata!(visit_rs::Named {
+                        name: #name,
+                        value: &visit_rs::Static::<#ty>::new(),
+                    } metadata: #metadata_ref);
+                    visit_rs::Build::build(&named, source)?
+                }
+            };
+            (index, built)
+        })
+        .collect();
+    built_fields.extend(
+        data.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| is_skipped(field))
+            .map(|(index, field)| {
+                let idx = match &field.ident {
+                    Some(name) => quote! { #name },
+                    None => {
+                        let index = syn::Index::from(index);
+                        quote! { #index }
+                    }
+                };
+                (index, quote! { #idx: Default::default() })
+            }),
+    );
+    // Build fields in declaration order
+    built_fields.sort_by_key(|(index, _)| *index);
+    let built_fields = built_fields.into_iter().map(|(_, built)| built);
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::FromFields<__visit_rs__V> for #ident #ty_generics #where_clause {
+            fn from_fields(
+                source: &mut __visit_rs__V,
+            ) -> Result<Self, <__visit_rs__V as visit_rs::Source>::Error> {
+                Ok(Self {
+                    #(#built_fields,)*
+                })
+            }
+        }
+    })
+}
+
+fn derive_dyn_visit_fields(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let ident = &ast.ident;
+    let (_, ty_generics, _) = ast.generics.split_for_impl();
+
+    // Bounds are higher-ranked so that types with non-`DynVisit` fields still compile
+    let mut generics = ast.generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    push_field_bounds(
+        predicates,
+        ast,
+        field_iter(&data.fields).map(|(_, field)| field),
+        BoundKind::DynVisit,
+        |field| {
+            let ty = &field.ty;
+            if flatten::is_flattened(field) {
+                return vec![
+                    parse_quote! { for<'__visit_rs__dyn> #ty: visit_rs::dynamic::DynVisitFields },
+                ];
+            }
+            vec![parse_quote! { for<'__visit_rs__dyn> #ty: visit_rs::dynamic::DynVisit }]
+        },
+    )?;
+    let fields_where_clause = generics.where_clause.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote! {
+            for<'__visit_rs__dyn> #ident #ty_generics: Sized
+        });
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    // Extract field metadata
+    let field_metas: Vec<_> = field_iter(&data.fields)
+        .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
+        .collect();
+
+    let visit_fields = field_name_idx_iter(ast, &data.fields)
+        .zip(field_iter(&data.fields))
+        .zip(flattened_iter(&data.fields))
+        .enumerate()
+        .map(|(num, (((name, idx), (_, field)), flattened))| {
+            if flattened.is_some() {
+                return Ok(quote! {
+                    visit_rs::dynamic::DynVisitFields::dyn_visit_fields(&self.#idx, visitor);
+                });
+            }
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            // Double reference so that unsized fields coerce to `dyn DynVisit`
+            let visit = quote! {
+                visitor.visit_field(visit_rs::__with_metadata!(visit_rs::Named {
+                    name: #name,
+                    value: &&self.#idx,
+                } metadata: #metadata_ref));
+            };
+            follow_serde::guard(ast, field, &idx, visit, quote! {})
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?;
+
+    Ok(quote! {
+        impl #impl_generics visit_rs::dynamic::DynVisitFields for #ident #ty_generics #fields_where_clause {
+            fn dyn_visit_fields(&self, visitor: &mut dyn visit_rs::dynamic::DynVisitor) {
+                #(#visit_fields)*
+            }
+        }
+
+        impl #impl_generics visit_rs::dynamic::DynVisit for #ident #ty_generics #where_clause {
+            fn dyn_visit(&self, visitor: &mut dyn visit_rs::dynamic::DynVisitor) {
+                visitor.visit_struct(&<Self as visit_rs::StructInfo>::DATA, self)
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_static(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsStatic },
+        &syn::parse_quote! { visit_rs::Visit },
+        None,
+        false,
+        true,
+    )?;
+
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_impl = field_iter(&data.fields)
+        .enumerate()
+        .map(|(num, (_, field))| {
+            let ty = &field.ty;
+            if flatten::is_flattened(field) {
+                let iter = quote! {
+                    <#ty as visit_rs::VisitFieldsStatic<__visit_rs__V>>::visit_fields_static(#visitor)
+                };
+                return flatten::arm(num, iter);
+            }
+            let value = with::static_value(
+                field,
+                quote! { &visit_rs::Static::<#ty>::new() },
+                BoundKind::Visit,
+            );
+            quote! {
+                #num => {
+                    pos += 1;
+                    Some(visit_rs::Visit::visit(#value, visitor))
+                }
+            }
+        });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_impl);
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_static<'__visit_rs__a>(
+                visitor: &'__visit_rs__a mut __visit_rs__V
+            ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
+                #iter
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_static_async(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldsStaticAsync },
+        &syn::parse_quote! { visit_rs::VisitAsync },
+        None,
+        true,
+        true,
+    )?;
+
+    let visit_fields_impl = field_iter(&data.fields).map(|(_, field)| {
+        let ty = &field.ty;
+        if flatten::is_flattened(field) {
+            return flatten::yield_stream(quote! {
+                <#ty as visit_rs::VisitFieldsStaticAsync<__visit_rs__V>>::visit_fields_static_async(&mut *visitor)
+            });
+        }
+        let value = with::static_value(
+            field,
+            quote! { &visit_rs::Static::<#ty>::new() },
+            BoundKind::VisitAsync,
+        );
+        yield_visit(quote! { visit_rs::VisitAsync::visit_async(#value, visitor).await })
+    });
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_fields_static_async<'__visit_rs__a>(
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl visit_rs::lib::futures::Stream<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                visit_rs::lib::async_stream::stream! {
+                    #(#visit_fields_impl)*
+                    #[allow(unreachable_code)]
+                    if false {
+                        yield unreachable!() as <__visit_rs__V as visit_rs::Visitor>::Result
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_fields_static_named(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let _ident = &ast.ident;
 
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsStaticNamed },
         &syn::parse_quote! { visit_rs::Visit },
         Some(&syn::parse_quote! { visit_rs::Named }),
         false,
         true,
-    );
+    )?;
 
     let rename_all_rule = get_rename_all_attribute(ast);
 
@@ -668,58 +1805,60 @@ fn derive_visit_fields_static_named(
                 quote! { None }
             };
 
-            let metadata_ref = if !field_metas[num].is_empty() {
-                let metas = &field_metas[num];
-                let count = metas.len();
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            let value = with::static_value(
+                field,
                 quote! {
-                    if cfg!(feature = "meta") {
-                        const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                        &META
-                    } else {
-                        &[]
+                    {
+                        static __VISIT_RS_STATIC: visit_rs::Static<()> = visit_rs::Static::new();
+                        unsafe {
+                            // SAFETY: Static<T> is zero-sized and contains only PhantomData,
+                            // so transmuting from &Static<()> to &Static<#ty> is safe
+                            &*(&__VISIT_RS_STATIC as *const visit_rs::Static<()> as *const visit_rs::Static<#ty>)
+                        }
                     }
-                }
-            } else {
-                quote! { &[] }
-            };
+                },
+                BoundKind::Visit,
+            );
 
-            (name, ty, metadata_ref)
+            (name, value, metadata_ref)
         });
 
-    let visit_fields_named_impl = field_name_type_iter.enumerate().map(|(num, (name, ty, metadata_ref))| {
-        quote! {
-            #num => {
-                pos += 1;
-                {
-                    static __VISIT_RS_STATIC: visit_rs::Static<()> = visit_rs::Static::new();
-                    let named = visit_rs::Named {
-                        name: #name,
-                        #[cfg(feature = "meta")]
-                        metadata: #metadata_ref,
-                        value: unsafe {
-                            // SAFETY: Static<T> is zero-sized and contains only PhantomData,
-                            // so transmuting from &Static<()> to &Static<#ty> is safe
-                            &*(&__VISIT_RS_STATIC as *const visit_rs::Static<()> as *const visit_rs::Static<#ty>)
-                        },
+    let visitor = quote! { &mut *visitor };
+    let visit_fields_named_impl =
+        field_name_type_iter
+            .zip(flattened_iter(&data.fields))
+            .enumerate()
+            .map(|(num, ((name, value, metadata_ref), flattened))| {
+                if let Some((_, ty)) = flattened {
+                    let iter = quote! {
+                        <#ty as visit_rs::VisitFieldsStaticNamed<__visit_rs__V>>::visit_fields_static_named(#visitor)
                     };
-                    Some(visit_rs::Visit::visit(&named, visitor))
+                    return flatten::arm(num, iter);
                 }
-            }
-        }
-    });
+                quote! {
+                    #num => {
+                        pos += 1;
+                        {
+                            let named = visit_rs::__with_metadata!(visit_rs::Named {
+                                name: #name,
+                                value: #value,
+                            } metadata: #metadata_ref);
+                            Some(visit_rs::Visit::visit(&named, visitor))
+                        }
+                    }
+                }
+            });
+
+    let iter = flatten::sync_iter(&data.fields, visit_fields_named_impl);
 
     Ok(quote! {
         #impl_t {
             fn visit_fields_static_named<'__visit_rs__a>(
                 visitor: &'__visit_rs__a mut __visit_rs__V
             ) -> impl Iterator<Item = <__visit_rs__V as visit_rs::Visitor>::Result> + '__visit_rs__a {
-                std::iter::from_fn({
-                    let mut pos = 0;
-                    move || match pos {
-                        #(#visit_fields_named_impl)*
-                        _ => None,
-                    }
-                })
+                #iter
             }
         }
     })
@@ -732,17 +1871,20 @@ fn derive_visit_fields_static_named_async(
     let _ident = &ast.ident;
 
     let impl_t = make_impl(
-        &ast,
+        ast,
         &data.fields,
         &syn::parse_quote! { visit_rs::VisitFieldsStaticNamedAsync },
         &syn::parse_quote! { visit_rs::VisitAsync },
         Some(&syn::parse_quote! { visit_rs::Named }),
         true,
         true,
-    );
+    )?;
 
     let rename_all_rule = get_rename_all_attribute(ast);
 
+    let yield_named =
+        yield_visit(quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await });
+
     // Extract field metadata
     let field_metas: Vec<_> = field_iter(&data.fields)
         .map(|(_, field)| attrs::extract_all_meta(&field.attrs))
@@ -760,39 +1902,41 @@ fn derive_visit_fields_static_named_async(
                 quote! { None }
             };
 
-            let metadata_ref = if !field_metas[num].is_empty() {
-                let metas = &field_metas[num];
-                let count = metas.len();
+            let metadata_ref = attrs::metadata_ref(&field_metas[num]);
+
+            let value = with::static_value(
+                field,
                 quote! {
-                    if cfg!(feature = "meta") {
-                        const META: [visit_rs::metadata::AttributeMeta; #count] = [#(#metas),*];
-                        &META
-                    } else {
-                        &[]
+                    {
+                        static __VISIT_RS_STATIC: visit_rs::Static<()> = visit_rs::Static::new();
+                        unsafe {
+                            // SAFETY: Static<T> is zero-sized and contains only PhantomData,
+                            // so transmuting from &Static<()> to &Static<#ty> is safe
+                            &*(&__VISIT_RS_STATIC as *const visit_rs::Static<()> as *const visit_rs::Static<#ty>)
+                        }
                     }
-                }
-            } else {
-                quote! { &[] }
-            };
+                },
+                BoundKind::VisitAsync,
+            );
 
-            (name, ty, metadata_ref)
+            (name, value, metadata_ref)
         });
 
-    let visit_fields_named_impl = field_name_type_iter.map(|(name, ty, metadata_ref)| {
+    let visit_fields_named_impl = field_name_type_iter
+        .zip(flattened_iter(&data.fields))
+        .map(|((name, value, metadata_ref), flattened)| {
+        if let Some((_, ty)) = flattened {
+            return flatten::yield_stream(quote! {
+                <#ty as visit_rs::VisitFieldsStaticNamedAsync<__visit_rs__V>>::visit_fields_static_named_async(&mut *visitor)
+            });
+        }
         quote! {
             {
-                static __VISIT_RS_STATIC: visit_rs::Static<()> = visit_rs::Static::new();
-                let named = visit_rs::Named {
+                let named = visit_rs::__with_metadata!(visit_rs::Named {
                     name: #name,
-                    #[cfg(feature = "meta")]
-                    metadata: #metadata_ref,
-                    value: unsafe {
-                        // SAFETY: Static<T> is zero-sized and contains only PhantomData,
-                        // so transmuting from &Static<()> to &Static<#ty> is safe
-                        &*(&__VISIT_RS_STATIC as *const visit_rs::Static<()> as *const visit_rs::Static<#ty>)
-                    },
-                };
-                yield visit_rs::VisitAsync::visit_async(&named, visitor).await;
+                    value: #value,
+                } metadata: #metadata_ref);
+                #yield_named
             }
         }
     });
@@ -818,11 +1962,332 @@ fn derive_visit_fields_static_named_async(
     })
 }
 
+/// `match` arms for looking up a single field by renamed name and by declaration
+/// index, each evaluating `visit` with `named` bound to the field's `Named`, and the
+/// fallback for names that match no arm, which asks each flattened field in turn
+/// through `lookup`, given the field's member and type
+fn field_lookup_arms(
+    ast: &DeriveInput,
+    data: &DataStruct,
+    is_static: bool,
+    kind: BoundKind,
+    visit: TokenStream,
+    lookup: impl Fn(&TokenStream, &syn::Type) -> TokenStream,
+) -> (Vec<TokenStream>, Vec<TokenStream>, TokenStream) {
+    let rename_all_rule = get_rename_all_attribute(ast);
+
+    let mut name_arms = Vec::new();
+    let mut index_arms = Vec::new();
+    let mut lookups = Vec::new();
+    let mut claimed: HashSet<_> = field_iter(&data.fields)
+        .filter_map(|(_, field)| get_field_rename(field, rename_all_rule))
+        .collect();
+    for ((index, field), flattened) in field_iter(&data.fields).zip(flattened_iter(&data.fields)) {
+        if let Some((idx, ty)) = flattened {
+            lookups.push(lookup(&idx, ty));
+            continue;
+        }
+        let ty = &field.ty;
+        let renamed = field
+            .ident
+            .as_ref()
+            .map(|_| get_field_rename(field, rename_all_rule).unwrap());
+        let name = match &renamed {
+            Some(renamed) => quote! { Some(#renamed) },
+            None => quote! { None },
+        };
+        let value = if is_static {
+            with::static_value(field, quote! { &visit_rs::Static::<#ty>::new() }, kind)
+        } else {
+            let idx = match &field.ident {
+                Some(ident) => quote! { #ident },
+                None => {
+                    let index = syn::Index::from(index);
+                    quote! { #index }
+                }
+            };
+            with::value(field, quote! { &self.#idx }, kind)
+        };
+
+        let metas = attrs::extract_all_meta(&field.attrs);
+        let metadata_ref = attrs::metadata_ref(&metas);
+
+        let body = quote! {
+            Some({
+                let named = visit_rs::__with_metadata!(visit_rs::Named {
+                    name: #name,
+                    value: #value,
+                } metadata: #metadata_ref);
+                #visit
+            })
+        };
+        if let Some(renamed) = &renamed {
+            // Any name already taken by an earlier field stays with it
+            let aliases: Vec<_> = get_field_aliases(field, rename_all_rule)
+                .into_iter()
+                .filter(|alias| claimed.insert(alias.clone()))
+                .collect();
+            name_arms.push(quote! { #renamed #(| #aliases)* => #body, });
+        }
+        index_arms.push(quote! { #index => #body, });
+    }
+    let fallback = quote! {
+        {
+            #(
+                if let Some(res) = #lookups {
+                    return Some(res);
+                }
+            )*
+            None
+        }
+    };
+    (name_arms, index_arms, fallback)
+}
+
+fn derive_visit_field(ast: &DeriveInput, data: &DataStruct) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitField },
+        &syn::parse_quote! { visit_rs::Visit },
+        Some(&syn::parse_quote! { visit_rs::Named }),
+        false,
+        false,
+    )?;
+
+    let (name_arms, index_arms, fallback) = field_lookup_arms(
+        ast,
+        data,
+        false,
+        BoundKind::Visit,
+        quote! { visit_rs::Visit::visit(&named, visitor) },
+        |idx, _| quote! { visit_rs::VisitField::visit_field_by_name(&self.#idx, name, visitor) },
+    );
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_field_by_name(
+                &self,
+                name: &str,
+                visitor: &mut __visit_rs__V,
+            ) -> Option<<__visit_rs__V as visit_rs::Visitor>::Result> {
+                match name {
+                    #(#name_arms)*
+                    _ => #fallback,
+                }
+            }
+
+            fn visit_field_by_index(
+                &self,
+                index: usize,
+                visitor: &mut __visit_rs__V,
+            ) -> Option<<__visit_rs__V as visit_rs::Visitor>::Result> {
+                match index {
+                    #(#index_arms)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_field_static(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldStatic },
+        &syn::parse_quote! { visit_rs::Visit },
+        Some(&syn::parse_quote! { visit_rs::Named }),
+        false,
+        true,
+    )?;
+
+    let (name_arms, index_arms, fallback) = field_lookup_arms(
+        ast,
+        data,
+        true,
+        BoundKind::Visit,
+        quote! { visit_rs::Visit::visit(&named, visitor) },
+        |_, ty| {
+            quote! {
+                <#ty as visit_rs::VisitFieldStatic<__visit_rs__V>>::visit_field_by_name_static(name, visitor)
+            }
+        },
+    );
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_field_by_name_static(
+                name: &str,
+                visitor: &mut __visit_rs__V,
+            ) -> Option<<__visit_rs__V as visit_rs::Visitor>::Result> {
+                match name {
+                    #(#name_arms)*
+                    _ => #fallback,
+                }
+            }
+
+            fn visit_field_by_index_static(
+                index: usize,
+                visitor: &mut __visit_rs__V,
+            ) -> Option<<__visit_rs__V as visit_rs::Visitor>::Result> {
+                match index {
+                    #(#index_arms)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_field_async(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldAsync },
+        &syn::parse_quote! { visit_rs::VisitAsync },
+        Some(&syn::parse_quote! { visit_rs::Named }),
+        true,
+        false,
+    )?;
+
+    let (name_arms, index_arms, fallback) = field_lookup_arms(
+        ast,
+        data,
+        false,
+        BoundKind::VisitAsync,
+        quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await },
+        |idx, _| {
+            quote! {
+                visit_rs::VisitFieldAsync::visit_field_by_name_async(&self.#idx, name, visitor).await
+            }
+        },
+    );
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_field_by_name_async<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                name: &'__visit_rs__a str,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl std::future::Future<Output = Option<<__visit_rs__V as visit_rs::Visitor>::Result>> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                async move {
+                    match name {
+                        #(#name_arms)*
+                        _ => #fallback,
+                    }
+                }
+            }
+
+            fn visit_field_by_index_async<'__visit_rs__a>(
+                &'__visit_rs__a self,
+                index: usize,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl std::future::Future<Output = Option<<__visit_rs__V as visit_rs::Visitor>::Result>> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                async move {
+                    match index {
+                        #(#index_arms)*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn derive_visit_field_static_async(
+    ast: &DeriveInput,
+    data: &DataStruct,
+) -> Result<TokenStream, syn::Error> {
+    let impl_t = make_impl(
+        ast,
+        &data.fields,
+        &syn::parse_quote! { visit_rs::VisitFieldStaticAsync },
+        &syn::parse_quote! { visit_rs::VisitAsync },
+        Some(&syn::parse_quote! { visit_rs::Named }),
+        true,
+        true,
+    )?;
+
+    let (name_arms, index_arms, fallback) = field_lookup_arms(
+        ast,
+        data,
+        true,
+        BoundKind::VisitAsync,
+        quote! { visit_rs::VisitAsync::visit_async(&named, visitor).await },
+        |_, ty| {
+            quote! {
+                <#ty as visit_rs::VisitFieldStaticAsync<__visit_rs__V>>::visit_field_by_name_static_async(name, visitor).await
+            }
+        },
+    );
+
+    Ok(quote! {
+        #impl_t {
+            fn visit_field_by_name_static_async<'__visit_rs__a>(
+                name: &'__visit_rs__a str,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl std::future::Future<Output = Option<<__visit_rs__V as visit_rs::Visitor>::Result>> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                async move {
+                    match name {
+                        #(#name_arms)*
+                        _ => #fallback,
+                    }
+                }
+            }
+
+            fn visit_field_by_index_static_async<'__visit_rs__a>(
+                index: usize,
+                visitor: &'__visit_rs__a mut __visit_rs__V,
+            ) -> impl std::future::Future<Output = Option<<__visit_rs__V as visit_rs::Visitor>::Result>> + Send + '__visit_rs__a
+            where
+                __visit_rs__V: Send,
+                <__visit_rs__V as visit_rs::Visitor>::Result: Send,
+            {
+                async move {
+                    match index {
+                        #(#index_arms)*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    })
+}
+
 mod enum_variants;
 
+/// See [`VisitFields`](derive@VisitFields) for `#[visit(crate = "...")]`,
+/// `#[visit(bound = "...")]` and `#[visit(with = "...")]`
+///
+/// An enum whose variants all have no fields can opt into `UnitVariants` with
+/// `#[visit(unit_variants)]`, and into `FromStr` and `Display` impls with
+/// `#[visit(from_str)]` and `#[visit(display)]`, all going by the names variants
+/// are visited and looked up by.
 #[proc_macro_derive(VisitVariants, attributes(visit))]
 pub fn derive_visit_variants(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let ast: DeriveInput = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as DeriveInput);
+    if let Err(e) = keys::check(&ast) {
+        return e.to_compile_error().into();
+    }
 
     let syn::Data::Enum(data) = &ast.data else {
         return syn::Error::new_spanned(&ast.ident, "VisitVariants can only be used on enums")
@@ -830,7 +2295,9 @@ pub fn derive_visit_variants(input: proc_macro::TokenStream) -> proc_macro::Toke
             .into();
     };
 
-    match enum_variants::derive_all_variant_traits(&ast, data) {
+    match enum_variants::derive_all_variant_traits(&ast, data)
+        .and_then(|tokens| wrap_impls(&ast, tokens))
+    {
         Ok(tokens) => tokens.into(),
         Err(e) => e.to_compile_error().into(),
     }
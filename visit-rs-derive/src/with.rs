@@ -0,0 +1,171 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Expr, ExprLit, Field, Lit, Meta, Path, WherePredicate, parse_quote};
+
+use crate::bounds::BoundKind;
+use crate::helpers::visit_items;
+
+/// How a field marked `#[visit(with = "...")]` or `#[visit(visit_with = "...")]`
+/// is visited
+pub enum VisitWith {
+    /// A module providing `visit`, `visit_async`, `visit_static`,
+    /// `visit_static_async`, `visit_mut` and, for owned visits, `visit_owned`
+    Module(Path),
+    /// A function standing in for `visit`
+    Fn(Path),
+}
+
+fn parse_visit_with(attrs: &[Attribute]) -> Result<Option<VisitWith>, syn::Error> {
+    let mut with = None;
+    for meta in visit_items(attrs) {
+        let Meta::NameValue(nv) = meta else {
+            continue;
+        };
+        let make: fn(Path) -> VisitWith = if nv.path.is_ident("with") {
+            VisitWith::Module
+        } else if nv.path.is_ident("visit_with") {
+            VisitWith::Fn
+        } else {
+            continue;
+        };
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) = &nv.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &nv.value,
+                "expected a string literal path, e.g. `with = \"my_module\"`",
+            ));
+        };
+        if with.is_some() {
+            return Err(syn::Error::new_spanned(
+                &nv.path,
+                "a field takes only one of `with` and `visit_with`",
+            ));
+        }
+        with = Some(make(s.parse()?));
+    }
+    Ok(with)
+}
+
+/// Reject malformed `with` and `visit_with` attributes on `fields` up front, so
+/// that generating each impl can read them infallibly
+pub fn check_fields<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Result<(), syn::Error> {
+    for field in fields {
+        parse_visit_with(&field.attrs)?;
+    }
+    Ok(())
+}
+
+/// Whether `field` is marked `with` or `visit_with`
+pub fn is_visited_with_any(field: &Field) -> bool {
+    matches!(parse_visit_with(&field.attrs), Ok(Some(_)))
+}
+
+/// Whether `field` is visited through a function in any of the impls of `kind`
+pub fn is_visited_with(field: &Field, kind: BoundKind) -> bool {
+    visit_fn(field, kind, false).is_some() || visit_fn(field, kind, true).is_some()
+}
+
+/// The function visiting `field` in the impls of `kind`, taking the field and the
+/// visitor, if it is routed through one
+fn visit_fn(field: &Field, kind: BoundKind, is_static: bool) -> Option<TokenStream> {
+    let with = parse_visit_with(&field.attrs).ok().flatten()?;
+    Some(match (with, kind, is_static) {
+        (VisitWith::Module(module), BoundKind::Visit, false) => quote! { #module::visit },
+        (VisitWith::Fn(f), BoundKind::Visit, false) => quote! { #f },
+        (VisitWith::Module(module), BoundKind::Visit, true) => {
+            quote! { |_, visitor| #module::visit_static(visitor) }
+        }
+        (VisitWith::Module(module), BoundKind::VisitAsync | BoundKind::VisitAsyncLocal, false) => {
+            quote! {
+                |value, visitor| std::boxed::Box::pin(#module::visit_async(value, visitor))
+            }
+        }
+        (VisitWith::Module(module), BoundKind::VisitAsync | BoundKind::VisitAsyncLocal, true) => {
+            quote! {
+                |_, visitor| std::boxed::Box::pin(#module::visit_static_async(visitor))
+            }
+        }
+        (VisitWith::Module(module), BoundKind::VisitMut, false) => quote! { #module::visit_mut },
+        (VisitWith::Module(module), BoundKind::VisitOwned, false) => {
+            quote! { #module::visit_owned }
+        }
+        _ => return None,
+    })
+}
+
+fn adapter(kind: BoundKind) -> TokenStream {
+    match kind {
+        BoundKind::VisitAsync => quote! { visit_rs::with::VisitAsyncWith },
+        BoundKind::VisitAsyncLocal => quote! { visit_rs::with::VisitAsyncLocalWith },
+        BoundKind::VisitMut => quote! { visit_rs::with::VisitMutWith },
+        BoundKind::VisitOwned => quote! { visit_rs::with::VisitOwnedWith },
+        _ => quote! { visit_rs::with::VisitWith },
+    }
+}
+
+/// The value visited in place of `value`, the field as the impls of `kind` visit
+/// it: a reference, a mutable reference, or the field itself
+pub fn value(field: &Field, value: TokenStream, kind: BoundKind) -> TokenStream {
+    match visit_fn(field, kind, false) {
+        Some(visit) => {
+            let adapter = adapter(kind);
+            match kind {
+                BoundKind::VisitMut => quote! { &mut #adapter::new(#value, #visit) },
+                BoundKind::VisitOwned => quote! { #adapter::new(#value, #visit) },
+                _ => quote! { &#adapter::new(#value, #visit) },
+            }
+        }
+        None => value,
+    }
+}
+
+/// The reference visited in place of `value`, a reference to a
+/// `visit_rs::Static<#ty>` for `field`, in the impls of `kind`
+pub fn static_value(field: &Field, value: TokenStream, kind: BoundKind) -> TokenStream {
+    match visit_fn(field, kind, true) {
+        Some(visit) => {
+            let adapter = adapter(kind);
+            let ty = &field.ty;
+            quote! { &#adapter::new(visit_rs::Static::<#ty>::new_ref(), #visit) }
+        }
+        None => value,
+    }
+}
+
+/// The predicates bounding visits of `field` in the impls of `kind`, if it is
+/// visited through a function. The adapter visits unconditionally, so only a
+/// `wrapper` around it (e.g. `visit_rs::Named`) needs a bound
+pub fn bound(
+    field: &Field,
+    kind: BoundKind,
+    is_static: bool,
+    wrapper: Option<TokenStream>,
+) -> Option<Vec<WherePredicate>> {
+    visit_fn(field, kind, is_static)?;
+    let Some(wrapper) = wrapper else {
+        return Some(Vec::new());
+    };
+    let adapter = adapter(kind);
+    let trait_path = match kind {
+        BoundKind::VisitAsync => quote! { visit_rs::VisitAsync },
+        BoundKind::VisitAsyncLocal => quote! { visit_rs::VisitAsyncLocal },
+        BoundKind::VisitMut => quote! { visit_rs::VisitMut },
+        BoundKind::VisitOwned => quote! { visit_rs::VisitOwned },
+        _ => quote! { visit_rs::Visit },
+    };
+    let ty = &field.ty;
+    let ty = if is_static {
+        quote! { visit_rs::Static<#ty> }
+    } else {
+        quote! { #ty }
+    };
+    let adapter = match kind {
+        BoundKind::VisitOwned => quote! { #adapter<#ty, __visit_rs__V> },
+        _ => quote! { #adapter<'__visit_rs__with, #ty, __visit_rs__V> },
+    };
+    Some(vec![parse_quote! {
+        for<'__visit_rs__with> #wrapper<'__visit_rs__with, #adapter>: #trait_path<__visit_rs__V>
+    }])
+}
@@ -0,0 +1,12 @@
+//! Derives from a crate without a `meta` feature of its own, whose metadata must
+//! follow visit-rs's `meta` feature rather than this crate's features
+
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+#[visit(some_attr = "value")]
+pub struct Settings {
+    #[visit(field_attr = "field_value")]
+    pub name: String,
+    pub retries: u32,
+}
@@ -0,0 +1,37 @@
+use visit_rs::metadata::MetaQuery;
+use visit_rs::{Named, StructInfo, Visit, VisitFieldsNamed, Visitor};
+use visit_rs_no_meta::Settings;
+
+/// Records the `field_attr` of each field it visits
+#[derive(Default)]
+struct FieldAttrs(Vec<Option<&'static str>>);
+
+impl Visitor for FieldAttrs {
+    type Result = ();
+}
+
+impl<'a, T> Visit<FieldAttrs> for Named<'a, T> {
+    fn visit(&self, visitor: &mut FieldAttrs) {
+        visitor.0.push(self.metadata.get_str("visit", "field_attr"));
+    }
+}
+
+#[test]
+fn test_metadata_without_meta_feature() {
+    assert_eq!(
+        Settings::DATA.metadata.get_str("visit", "some_attr"),
+        Some("value")
+    );
+    assert_eq!(
+        Settings::FIELDS[0].metadata.get_str("visit", "field_attr"),
+        Some("field_value")
+    );
+
+    let settings = Settings {
+        name: "retry".to_string(),
+        retries: 3,
+    };
+    let mut attrs = FieldAttrs::default();
+    settings.visit_fields_named(&mut attrs).for_each(drop);
+    assert_eq!(attrs.0, vec![Some("field_value"), None]);
+}
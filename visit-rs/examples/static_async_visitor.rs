@@ -101,14 +101,14 @@ async fn main() {
     );
     println!("  Config::DATA.field_count = {}", Config::DATA.field_count);
     assert_eq!(Config::DATA.name, "Config");
-    assert_eq!(Config::DATA.named_fields, true);
+    const { assert!(Config::DATA.named_fields) };
     assert_eq!(Config::DATA.field_count, 3);
 
     println!("\n  Empty::DATA.name = {}", Empty::DATA.name);
     println!("  Empty::DATA.named_fields = {}", Empty::DATA.named_fields);
     println!("  Empty::DATA.field_count = {}", Empty::DATA.field_count);
     assert_eq!(Empty::DATA.name, "EmptyStructure");
-    assert_eq!(Empty::DATA.named_fields, true);
+    const { assert!(Empty::DATA.named_fields) };
     assert_eq!(Empty::DATA.field_count, 0);
 
     println!("\n  Settings::DATA.name = {}", Settings::DATA.name);
@@ -121,7 +121,7 @@ async fn main() {
         Settings::DATA.field_count
     );
     assert_eq!(Settings::DATA.name, "ServerSettings");
-    assert_eq!(Settings::DATA.named_fields, true);
+    const { assert!(Settings::DATA.named_fields) };
     assert_eq!(Settings::DATA.field_count, 1);
 
     println!("\nAll async static visitors and StructInfo work!");
@@ -15,6 +15,12 @@ fn main() {
     println!("  DATA.name: {}", NamedStruct::DATA.name);
     println!("  DATA.named_fields: {}", NamedStruct::DATA.named_fields);
     println!("  DATA.field_count: {}", NamedStruct::DATA.field_count);
+    for field in NamedStruct::FIELDS {
+        println!(
+            "  FIELDS[{}]: {:?}: {}",
+            field.index, field.name, field.type_name
+        );
+    }
 
     println!("\nTupleStruct:");
     println!("  DATA.name: {}", TupleStruct::DATA.name);
@@ -22,11 +28,13 @@ fn main() {
     println!("  DATA.field_count: {}", TupleStruct::DATA.field_count);
 
     assert_eq!(NamedStruct::DATA.name, "NamedStruct");
-    assert!(NamedStruct::DATA.named_fields);
+    const { assert!(NamedStruct::DATA.named_fields) };
     assert_eq!(NamedStruct::DATA.field_count, 3);
+    assert_eq!(NamedStruct::FIELDS[1].name, Some("field2"));
+    assert_eq!(NamedStruct::FIELDS[1].type_name, "i32");
 
     assert_eq!(TupleStruct::DATA.name, "TupleStruct");
-    assert!(!TupleStruct::DATA.named_fields);
+    const { assert!(!TupleStruct::DATA.named_fields) };
     assert_eq!(TupleStruct::DATA.field_count, 3);
 
     println!("\nStructInfo trait works correctly!");
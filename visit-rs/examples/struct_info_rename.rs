@@ -26,7 +26,7 @@ fn main() {
     println!("  DATA.named_fields: {}", DefaultName::DATA.named_fields);
     println!("  DATA.field_count: {}", DefaultName::DATA.field_count);
     assert_eq!(DefaultName::DATA.name, "DefaultName");
-    assert_eq!(DefaultName::DATA.named_fields, true);
+    const { assert!(DefaultName::DATA.named_fields) };
     assert_eq!(DefaultName::DATA.field_count, 1);
 
     println!("\nRenamedWithVisit:");
@@ -37,7 +37,7 @@ fn main() {
     );
     println!("  DATA.field_count: {}", RenamedWithVisit::DATA.field_count);
     assert_eq!(RenamedWithVisit::DATA.name, "CustomName");
-    assert_eq!(RenamedWithVisit::DATA.named_fields, true);
+    const { assert!(RenamedWithVisit::DATA.named_fields) };
     assert_eq!(RenamedWithVisit::DATA.field_count, 1);
 
     println!("\nAnotherRenamed:");
@@ -45,7 +45,7 @@ fn main() {
     println!("  DATA.named_fields: {}", AnotherRenamed::DATA.named_fields);
     println!("  DATA.field_count: {}", AnotherRenamed::DATA.field_count);
     assert_eq!(AnotherRenamed::DATA.name, "AnotherCustomName");
-    assert_eq!(AnotherRenamed::DATA.named_fields, true);
+    const { assert!(AnotherRenamed::DATA.named_fields) };
     assert_eq!(AnotherRenamed::DATA.field_count, 2);
 
     println!("\nAll rename tests passed!");
@@ -0,0 +1,136 @@
+//! Object-safe visiting for visitors selected at runtime
+//!
+//! [`DynVisitor`] replaces the generic `V: Visitor` parameter with a fixed set of
+//! methods, so a `&mut dyn DynVisitor` can walk any type implementing
+//! [`DynVisit`]. The `VisitFields` and `VisitVariants` derives implement
+//! [`DynVisitFields`] and [`DynVisit`] whenever every field implements `DynVisit`.
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{EnumInfoData, Named, StructInfoData, Variant};
+
+pub trait DynVisitor: AsDynVisitor {
+    fn visit_bool(&mut self, value: bool);
+    fn visit_i64(&mut self, value: i64);
+    fn visit_u64(&mut self, value: u64);
+    fn visit_f64(&mut self, value: f64);
+    fn visit_str(&mut self, value: &str);
+    fn visit_char(&mut self, value: char) {
+        self.visit_str(value.encode_utf8(&mut [0; 4]))
+    }
+    fn visit_unit(&mut self) {}
+    /// Called for each field of a struct or variant
+    fn visit_field<'a>(&mut self, field: Named<'a, dyn DynVisit + 'a>) {
+        field.value.dyn_visit(self.as_dyn_visitor())
+    }
+    /// Called for a nested struct
+    fn visit_struct<'a>(&mut self, _info: &StructInfoData, fields: &'a (dyn DynVisitFields + 'a)) {
+        fields.dyn_visit_fields(self.as_dyn_visitor())
+    }
+    /// Called for a nested enum, with the variant it holds
+    fn visit_variant<'a>(
+        &mut self,
+        _info: &EnumInfoData,
+        variant: Variant<'a, dyn DynVisitFields + 'a>,
+    ) {
+        variant.value.dyn_visit_fields(self.as_dyn_visitor())
+    }
+}
+
+/// Upcasts a [`DynVisitor`] to a trait object, so that provided methods can
+/// pass `self` on
+pub trait AsDynVisitor {
+    fn as_dyn_visitor(&mut self) -> &mut dyn DynVisitor;
+}
+impl<T: DynVisitor> AsDynVisitor for T {
+    fn as_dyn_visitor(&mut self) -> &mut dyn DynVisitor {
+        self
+    }
+}
+
+pub trait DynVisit {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor);
+}
+
+pub trait DynVisitFields {
+    /// Calls [`DynVisitor::visit_field`] for each field (of the current variant, for enums)
+    fn dyn_visit_fields(&self, visitor: &mut dyn DynVisitor);
+}
+
+macro_rules! dyn_visit_as {
+    ($method:ident($as:ty): $($ty:ty),+) => {
+        $(
+            impl DynVisit for $ty {
+                fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+                    visitor.$method(*self as $as)
+                }
+            }
+        )+
+    };
+}
+
+dyn_visit_as!(visit_i64(i64): i8, i16, i32, i64, isize);
+dyn_visit_as!(visit_u64(u64): u8, u16, u32, u64, usize);
+dyn_visit_as!(visit_f64(f64): f32, f64);
+
+impl DynVisit for bool {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        visitor.visit_bool(*self)
+    }
+}
+
+impl DynVisit for char {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        visitor.visit_char(*self)
+    }
+}
+
+impl DynVisit for () {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        visitor.visit_unit()
+    }
+}
+
+impl DynVisit for str {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        visitor.visit_str(self)
+    }
+}
+
+impl DynVisit for String {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        visitor.visit_str(self)
+    }
+}
+
+impl<T: DynVisit + ?Sized> DynVisit for &T {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        (**self).dyn_visit(visitor)
+    }
+}
+
+impl<T: DynVisit + ?Sized> DynVisit for Box<T> {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        (**self).dyn_visit(visitor)
+    }
+}
+
+impl<T: DynVisit + ?Sized> DynVisit for Rc<T> {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        (**self).dyn_visit(visitor)
+    }
+}
+
+impl<T: DynVisit + ?Sized> DynVisit for Arc<T> {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        (**self).dyn_visit(visitor)
+    }
+}
+
+impl<T: DynVisit + ToOwned + ?Sized> DynVisit for Cow<'_, T> {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        (**self).dyn_visit(visitor)
+    }
+}
@@ -1,6 +1,9 @@
 use std::marker::PhantomData;
+use std::ops::ControlFlow;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
 
-use futures::Stream;
+use futures::{Stream, StreamExt};
 
 pub use visit_rs_derive::*;
 
@@ -10,13 +13,66 @@ pub mod serde;
 #[cfg(feature = "meta")]
 pub mod metadata;
 
+#[cfg(feature = "rayon")]
+pub mod rayon;
+
+pub mod dynamic;
+pub mod path;
+pub mod structural;
+pub mod with;
+
 pub mod lib {
     pub use async_stream;
     pub use futures;
+    #[cfg(feature = "rayon")]
+    pub use rayon;
+}
+
+/// Expands its input only when visit-rs is built with the `rayon` feature, so
+/// derived impls follow visit-rs's features rather than the deriving crate's. A
+/// `#[cfg(feature = "...")]` in derived code would be checked against the features
+/// of the crate deriving, which need not have one by that name
+#[doc(hidden)]
+#[cfg(feature = "rayon")]
+#[macro_export]
+macro_rules! __if_rayon {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "rayon"))]
+#[macro_export]
+macro_rules! __if_rayon {
+    ($($tt:tt)*) => {};
+}
+
+/// Expands to the struct literal `$path { $fields metadata: $metadata }` when
+/// visit-rs is built with the `meta` feature, and to `$path { $fields }` otherwise,
+/// for the same reason as [`__if_rayon!`]. `$fields` must end with a comma
+#[doc(hidden)]
+#[cfg(feature = "meta")]
+#[macro_export]
+macro_rules! __with_metadata {
+    ($($path:ident)::+ { $($fields:tt)* } metadata: $metadata:expr) => {
+        $($path)::+ { $($fields)* metadata: $metadata }
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "meta"))]
+#[macro_export]
+macro_rules! __with_metadata {
+    ($($path:ident)::+ { $($fields:tt)* } metadata: $metadata:expr) => {
+        $($path)::+ { $($fields)* }
+    };
 }
 
 pub trait Visitor {
     type Result;
+    /// Whether `result` should stop the visit, skipping all remaining fields
+    fn is_break(_result: &Self::Result) -> bool {
+        false
+    }
 }
 
 pub trait WrapperVisitor<'a>: Visitor {
@@ -25,6 +81,12 @@ pub trait WrapperVisitor<'a>: Visitor {
     fn wrap(visitor: &'a mut Self::Inner) -> Self;
 }
 
+/// A visitor that can hand out independent copies of itself, so that fields can
+/// be visited concurrently
+pub trait ForkVisitor: Visitor + Sized {
+    fn fork(&self) -> Self;
+}
+
 pub trait Visit<V: Visitor> {
     fn visit(&self, visitor: &mut V) -> V::Result;
 }
@@ -36,21 +98,74 @@ pub trait VisitAsync<V: Visitor> {
         V::Result: Send;
 }
 
+/// Like [`VisitAsync`], without requiring anything to be `Send`, for visitors
+/// holding e.g. `Rc` or `RefCell` on a single-threaded runtime
+pub trait VisitAsyncLocal<V: Visitor> {
+    fn visit_async_local<'a>(&'a self, visitor: &'a mut V) -> impl Future<Output = V::Result> + 'a;
+}
+
+pub trait VisitMut<V: Visitor> {
+    fn visit_mut(&mut self, visitor: &mut V) -> V::Result;
+}
+
+pub trait VisitOwned<V: Visitor> {
+    fn visit_owned(self, visitor: &mut V) -> V::Result;
+}
+
 pub trait StructInfo {
     const DATA: StructInfoData;
+    /// Every declared field, including skipped ones, in declaration order
+    const FIELDS: &'static [FieldInfo] = Self::DATA.fields;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct StructInfoData {
     pub name: &'static str,
     pub named_fields: bool,
+    /// The number of visited fields, excluding skipped ones and counting the fields
+    /// of a flattened field in its place
     pub field_count: usize,
+    pub fields: &'static [FieldInfo],
+    /// Where this describes an enum variant, its place in the enum
+    pub variant: Option<VariantInfo>,
+    #[cfg(feature = "meta")]
+    pub metadata: &'static [metadata::AttributeMeta],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariantInfo {
+    /// The position of the variant in the declaration
+    pub index: usize,
+    /// The explicit discriminant as written, e.g. `"0x10"`
+    pub discriminant: Option<&'static str>,
+    /// The value of the discriminant, where it is known at compile time: always
+    /// for enums without fields, otherwise where it follows from integer literals.
+    /// `None` for a `repr(u128)` value above `i128::MAX`
+    pub discriminant_value: Option<i128>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// The identifier as declared, or `None` for tuple fields
+    pub ident: Option<&'static str>,
+    /// The name after rename rules are applied, or `None` for tuple fields
+    pub name: Option<&'static str>,
+    /// The position of the field in the declaration
+    pub index: usize,
+    /// The type as written in the declaration
+    pub type_name: &'static str,
+    pub skip: bool,
+    /// Whether the field's own fields are visited in its place
+    pub flatten: bool,
     #[cfg(feature = "meta")]
     pub metadata: &'static [metadata::AttributeMeta],
 }
 
 pub trait VisitFields<V: Visitor>: StructInfo {
     fn visit_fields<'a>(&'a self, visitor: &'a mut V) -> impl Iterator<Item = V::Result> + 'a;
+    fn try_visit_fields(&self, visitor: &mut V) -> ControlFlow<V::Result> {
+        first_break::<V>(self.visit_fields(visitor))
+    }
 }
 
 pub trait VisitFieldsCovered<V: Visitor>: StructInfo {
@@ -72,6 +187,29 @@ pub trait VisitFieldsAsync<V: Visitor>: StructInfo {
     where
         V: Send,
         V::Result: Send;
+    fn try_visit_fields_async<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send,
+    {
+        first_break_async::<V>(self.visit_fields_async(visitor))
+    }
+}
+
+pub trait VisitFieldsAsyncLocal<V: Visitor>: StructInfo {
+    fn visit_fields_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Stream<Item = V::Result> + 'a;
+    fn try_visit_fields_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + 'a {
+        first_break_async::<V>(self.visit_fields_async_local(visitor))
+    }
 }
 
 pub trait VisitFieldsCoveredAsync<V: Visitor>: StructInfo {
@@ -85,7 +223,9 @@ pub trait VisitFieldsCoveredAsync<V: Visitor>: StructInfo {
 }
 
 pub trait VisitFieldsStaticAsync<V: Visitor>: StructInfo {
-    fn visit_fields_static_async<'a>(visitor: &'a mut V) -> impl Stream<Item = V::Result> + 'a
+    fn visit_fields_static_async<'a>(
+        visitor: &'a mut V,
+    ) -> impl Stream<Item = V::Result> + Send + 'a
     where
         V: Send,
         V::Result: Send;
@@ -94,6 +234,9 @@ pub trait VisitFieldsStaticAsync<V: Visitor>: StructInfo {
 pub trait VisitFieldsNamed<V: Visitor>: StructInfo {
     fn visit_fields_named<'a>(&'a self, visitor: &'a mut V)
     -> impl Iterator<Item = V::Result> + 'a;
+    fn try_visit_fields_named(&self, visitor: &mut V) -> ControlFlow<V::Result> {
+        first_break::<V>(self.visit_fields_named(visitor))
+    }
 }
 
 pub trait VisitFieldsStaticNamed<V: Visitor>: StructInfo {
@@ -108,6 +251,29 @@ pub trait VisitFieldsNamedAsync<V: Visitor>: StructInfo {
     where
         V: Send,
         V::Result: Send;
+    fn try_visit_fields_named_async<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send,
+    {
+        first_break_async::<V>(self.visit_fields_named_async(visitor))
+    }
+}
+
+pub trait VisitFieldsNamedAsyncLocal<V: Visitor>: StructInfo {
+    fn visit_fields_named_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Stream<Item = V::Result> + 'a;
+    fn try_visit_fields_named_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + 'a {
+        first_break_async::<V>(self.visit_fields_named_async_local(visitor))
+    }
 }
 
 pub trait VisitFieldsStaticNamedAsync<V: Visitor>: StructInfo {
@@ -119,6 +285,120 @@ pub trait VisitFieldsStaticNamedAsync<V: Visitor>: StructInfo {
         V::Result: Send;
 }
 
+/// Visits up to `limit` fields at once, each with its own fork of the visitor
+///
+/// Results are yielded as they complete, tagged with the field's declaration
+/// index (see [`FieldInfo::index`]), or a flattened field's fields with that
+/// field's index. A `limit` of 0 is taken as 1. [`Visitor::is_break`] is not
+/// consulted; drop the stream to stop early.
+pub trait VisitFieldsConcurrent<V: ForkVisitor>: StructInfo {
+    fn visit_fields_concurrent<'a>(
+        &'a self,
+        visitor: &V,
+        limit: usize,
+    ) -> impl Stream<Item = (usize, V::Result)> + Send + 'a
+    where
+        V: Send + 'a,
+        V::Result: Send;
+}
+
+pub trait VisitFieldsNamedConcurrent<V: ForkVisitor>: StructInfo {
+    fn visit_fields_named_concurrent<'a>(
+        &'a self,
+        visitor: &V,
+        limit: usize,
+    ) -> impl Stream<Item = (usize, V::Result)> + Send + 'a
+    where
+        V: Send + 'a,
+        V::Result: Send;
+}
+
+/// Visits a single field as a [`Named`], looked up by its renamed name or by its
+/// declaration index (see [`FieldInfo::index`])
+///
+/// Any other name the field deserializes from, such as an `alias`, finds it too.
+/// Returns `None` if there is no such field, or if it is skipped. The fields of a
+/// flattened field are found by name only
+pub trait VisitField<V: Visitor>: StructInfo {
+    fn visit_field_by_name(&self, name: &str, visitor: &mut V) -> Option<V::Result>;
+    fn visit_field_by_index(&self, index: usize, visitor: &mut V) -> Option<V::Result>;
+}
+
+pub trait VisitFieldStatic<V: Visitor>: StructInfo {
+    fn visit_field_by_name_static(name: &str, visitor: &mut V) -> Option<V::Result>;
+    fn visit_field_by_index_static(index: usize, visitor: &mut V) -> Option<V::Result>;
+}
+
+pub trait VisitFieldAsync<V: Visitor>: StructInfo {
+    fn visit_field_by_name_async<'a>(
+        &'a self,
+        name: &'a str,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = Option<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send;
+    fn visit_field_by_index_async<'a>(
+        &'a self,
+        index: usize,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = Option<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send;
+}
+
+pub trait VisitFieldStaticAsync<V: Visitor>: StructInfo {
+    fn visit_field_by_name_static_async<'a>(
+        name: &'a str,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = Option<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send;
+    fn visit_field_by_index_static_async<'a>(
+        index: usize,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = Option<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send;
+}
+
+pub trait VisitFieldsMut<V: Visitor>: StructInfo {
+    fn visit_fields_mut<'a>(
+        &'a mut self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
+
+pub trait VisitFieldsNamedMut<V: Visitor>: StructInfo {
+    fn visit_fields_named_mut<'a>(
+        &'a mut self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
+
+/// Derived for structs marked `#[visit(owned)]`
+pub trait VisitFieldsOwned<V: Visitor>: StructInfo {
+    fn visit_fields_owned<'a>(self, visitor: &'a mut V) -> impl Iterator<Item = V::Result> + 'a
+    where
+        Self: 'a;
+}
+
+pub trait Source: Visitor {
+    type Error;
+}
+
+pub trait Build<S: Source> {
+    type Output;
+    fn build(&self, source: &mut S) -> Result<Self::Output, S::Error>;
+}
+
+pub trait FromFields<S: Source>: StructInfo + Sized {
+    fn from_fields(source: &mut S) -> Result<Self, S::Error>;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Named<'a, T: ?Sized> {
     pub name: Option<&'static str>,
@@ -127,6 +407,14 @@ pub struct Named<'a, T: ?Sized> {
     pub value: &'a T,
 }
 
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct NamedMut<'a, T: ?Sized> {
+    pub name: Option<&'static str>,
+    #[cfg(feature = "meta")]
+    pub metadata: &'static [metadata::AttributeMeta],
+    pub value: &'a mut T,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Covered<'a, T: ?Sized>(pub &'a T);
 
@@ -147,7 +435,7 @@ impl<T: ?Sized> Default for Static<T> {
 }
 impl<T: ?Sized> Clone for Static<T> {
     fn clone(&self) -> Self {
-        Self::new()
+        *self
     }
 }
 impl<T: ?Sized> Copy for Static<T> {}
@@ -171,21 +459,150 @@ impl<T: ?Sized> Static<T> {
     }
 }
 
+/// Ends the wrapped iterator after the first result that `V` reports as a break
+pub struct UntilBreak<V: Visitor, I> {
+    iter: Option<I>,
+    _phantom: PhantomData<fn() -> V>,
+}
+impl<V: Visitor, I> UntilBreak<V, I> {
+    pub fn new(iter: I) -> Self {
+        UntilBreak {
+            iter: Some(iter),
+            _phantom: PhantomData,
+        }
+    }
+}
+impl<V: Visitor, I: Iterator<Item = V::Result>> Iterator for UntilBreak<V, I> {
+    type Item = V::Result;
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.iter.as_mut()?.next()?;
+        if V::is_break(&res) {
+            self.iter = None;
+        }
+        Some(res)
+    }
+}
+
+/// Iterates a stream that never waits, by polling it in place
+///
+/// A derived iterator over a struct with flattened fields is written as a stream,
+/// so that the visitor can pass from one field's iterator to the next.
+///
+/// # Panics
+///
+/// If the stream is pending. The derived streams only call sync impls and never
+/// await, so they are always ready.
+#[doc(hidden)]
+pub struct __StreamIter<S>(Pin<Box<S>>);
+impl<S: Stream> __StreamIter<S> {
+    pub fn new(stream: S) -> Self {
+        __StreamIter(Box::pin(stream))
+    }
+}
+impl<S: Stream> Iterator for __StreamIter<S> {
+    type Item = S::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut cx = Context::from_waker(Waker::noop());
+        match self.0.as_mut().poll_next(&mut cx) {
+            Poll::Ready(item) => item,
+            Poll::Pending => unreachable!("the fields of a sync visit never wait"),
+        }
+    }
+}
+
+fn first_break<V: Visitor>(mut results: impl Iterator<Item = V::Result>) -> ControlFlow<V::Result> {
+    match results.find(V::is_break) {
+        Some(res) => ControlFlow::Break(res),
+        None => ControlFlow::Continue(()),
+    }
+}
+
+async fn first_break_async<V: Visitor>(
+    results: impl Stream<Item = V::Result>,
+) -> ControlFlow<V::Result> {
+    let mut results = std::pin::pin!(results);
+    while let Some(res) = results.next().await {
+        if V::is_break(&res) {
+            return ControlFlow::Break(res);
+        }
+    }
+    ControlFlow::Continue(())
+}
+
 pub trait EnumInfo {
     const DATA: EnumInfoData;
     fn variants() -> impl IntoIterator<Item = StructInfoData> + Send + Sync + 'static;
     fn variant_info(&self) -> StructInfoData;
+    /// Looks a variant up by the name it is visited as, or by any other name serde
+    /// accepts for it: its `rename(deserialize = "...")` or an `alias`
     fn variant_info_by_name(name: &str) -> Option<StructInfoData>;
+    /// The position of this value's variant in the declaration
+    ///
+    /// The derive implements this directly. The default reads it from
+    /// [`StructInfoData::variant`], or failing that finds the variant by name in
+    /// [`EnumInfo::variants`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `variant_info` returns no `variant` and its name is not among
+    /// `variants`.
+    fn variant_index(&self) -> usize {
+        let info = self.variant_info();
+        info.variant.map_or_else(
+            || {
+                Self::variants()
+                    .into_iter()
+                    .position(|variant| variant.name == info.name)
+                    .expect("variant_info is one of variants")
+            },
+            |variant| variant.index,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct EnumInfoData {
     pub name: &'static str,
     pub variant_count: usize,
+    /// The contents of the enum's `#[repr(...)]`, e.g. `"u8"` or `"C, u16"`
+    pub repr: Option<&'static str>,
     #[cfg(feature = "meta")]
     pub metadata: &'static [metadata::AttributeMeta],
 }
 
+/// The variants of an enum whose variants all have no fields as a set of values,
+/// derived by `VisitVariants` for an enum marked `#[visit(unit_variants)]`
+pub trait UnitVariants: EnumInfo + Sized + 'static {
+    /// Every variant, in declaration order
+    const ALL: &'static [Self];
+    /// Every variant, in declaration order
+    fn all_values() -> impl Iterator<Item = Self> + Send + Sync + 'static;
+    /// The variant visited as `name`, or accepted by any other name serde accepts
+    /// for it
+    ///
+    /// Not named `from_variant_name`, since [`FromVariantFields::from_variant_name`]
+    /// already takes that name on enums deriving both.
+    fn variant_by_name(name: &str) -> Option<Self>;
+    /// The name this variant is visited as
+    fn as_variant_name(&self) -> &'static str;
+}
+
+/// The error from the `FromStr` impl `VisitVariants` derives for an enum marked
+/// `#[visit(from_str)]`, for a name none of its variants goes by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseVariantError {
+    pub enum_name: &'static str,
+    pub name: String,
+}
+
+impl std::fmt::Display for ParseVariantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown variant `{}` of {}", self.name, self.enum_name)
+    }
+}
+
+impl std::error::Error for ParseVariantError {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Variant<'a, T: ?Sized> {
     pub info: StructInfoData,
@@ -205,6 +622,9 @@ pub trait VisitVariantFields<V: Visitor>: EnumInfo {
         &'a self,
         visitor: &'a mut V,
     ) -> impl Iterator<Item = V::Result> + 'a;
+    fn try_visit_variant_fields(&self, visitor: &mut V) -> ControlFlow<V::Result> {
+        first_break::<V>(self.visit_variant_fields(visitor))
+    }
 }
 
 pub trait VisitVariantFieldsCovered<V: Visitor>: EnumInfo {
@@ -229,6 +649,29 @@ pub trait VisitVariantFieldsAsync<V: Visitor>: EnumInfo {
     where
         V: Send,
         V::Result: Send;
+    fn try_visit_variant_fields_async<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send,
+    {
+        first_break_async::<V>(self.visit_variant_fields_async(visitor))
+    }
+}
+
+pub trait VisitVariantFieldsAsyncLocal<V: Visitor>: EnumInfo {
+    fn visit_variant_fields_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Stream<Item = V::Result> + 'a;
+    fn try_visit_variant_fields_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + 'a {
+        first_break_async::<V>(self.visit_variant_fields_async_local(visitor))
+    }
 }
 
 pub trait VisitVariantFieldsCoveredAsync<V: Visitor>: EnumInfo {
@@ -256,6 +699,9 @@ pub trait VisitVariantFieldsNamed<V: Visitor>: EnumInfo {
         &'a self,
         visitor: &'a mut V,
     ) -> impl Iterator<Item = V::Result> + 'a;
+    fn try_visit_variant_fields_named(&self, visitor: &mut V) -> ControlFlow<V::Result> {
+        first_break::<V>(self.visit_variant_fields_named(visitor))
+    }
 }
 
 pub trait VisitVariantFieldsStaticNamed<V: Visitor>: EnumInfo {
@@ -273,6 +719,29 @@ pub trait VisitVariantFieldsNamedAsync<V: Visitor>: EnumInfo {
     where
         V: Send,
         V::Result: Send;
+    fn try_visit_variant_fields_named_async<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send,
+    {
+        first_break_async::<V>(self.visit_variant_fields_named_async(visitor))
+    }
+}
+
+pub trait VisitVariantFieldsNamedAsyncLocal<V: Visitor>: EnumInfo {
+    fn visit_variant_fields_named_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Stream<Item = V::Result> + 'a;
+    fn try_visit_variant_fields_named_async_local<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Future<Output = ControlFlow<V::Result>> + 'a {
+        first_break_async::<V>(self.visit_variant_fields_named_async_local(visitor))
+    }
 }
 
 pub trait VisitVariantFieldsStaticNamedAsync<V: Visitor>: EnumInfo {
@@ -284,3 +753,49 @@ pub trait VisitVariantFieldsStaticNamedAsync<V: Visitor>: EnumInfo {
         V: Send,
         V::Result: Send;
 }
+
+/// Like [`VisitFieldsConcurrent`], with results tagged with the field's index in
+/// the variant
+pub trait VisitVariantFieldsConcurrent<V: ForkVisitor>: EnumInfo {
+    fn visit_variant_fields_concurrent<'a>(
+        &'a self,
+        visitor: &V,
+        limit: usize,
+    ) -> impl Stream<Item = (usize, V::Result)> + Send + 'a
+    where
+        V: Send + 'a,
+        V::Result: Send;
+}
+
+pub trait VisitVariantFieldsMut<V: Visitor>: EnumInfo {
+    fn visit_variant_fields_mut<'a>(
+        &'a mut self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
+
+pub trait VisitVariantFieldsNamedMut<V: Visitor>: EnumInfo {
+    fn visit_variant_fields_named_mut<'a>(
+        &'a mut self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
+
+/// Derived for enums marked `#[visit(owned)]`
+pub trait VisitVariantFieldsOwned<V: Visitor>: EnumInfo {
+    fn visit_variant_fields_owned<'a>(
+        self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a
+    where
+        Self: 'a;
+}
+
+pub trait FromVariantFields<S: Source>: EnumInfo + Sized {
+    /// `None` if `info` does not describe a variant of `Self`
+    fn from_variant_fields(info: &StructInfoData, source: &mut S)
+    -> Option<Result<Self, S::Error>>;
+    fn from_variant_name(name: &str, source: &mut S) -> Option<Result<Self, S::Error>> {
+        Self::variant_info_by_name(name).and_then(|info| Self::from_variant_fields(&info, source))
+    }
+}
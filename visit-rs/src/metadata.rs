@@ -39,3 +39,167 @@ pub enum AttributeMeta {
         tokens: &'static str,
     },
 }
+
+impl MetaValue {
+    /// The value of a string literal, e.g. `"foo"`
+    pub fn as_str(&self) -> Option<&'static str> {
+        match self {
+            MetaValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The value of a boolean literal
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            MetaValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The value of an integer literal that fits in an `i64`
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            MetaValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The value of a float or integer literal, ignoring any type suffix
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetaValue::Float(s) => s
+                .trim_end_matches("f32")
+                .trim_end_matches("f64")
+                .replace('_', "")
+                .parse()
+                .ok(),
+            MetaValue::Int(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// A path or identifier, e.g. `Self::default` for `default = Self::default`
+    pub fn as_path(&self) -> Option<&'static str> {
+        match self {
+            MetaValue::Path(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl AttributeMeta {
+    /// The name this entry is looked up by: the path of a flag, list or unparsed
+    /// attribute, or the name of a name-value pair
+    pub fn name(&self) -> &'static str {
+        match self {
+            AttributeMeta::Path { path }
+            | AttributeMeta::List { path, .. }
+            | AttributeMeta::Unparsed { path, .. } => path,
+            AttributeMeta::NameValue { name, .. } => name,
+        }
+    }
+
+    /// The value of a name-value pair
+    pub fn value(&self) -> Option<&MetaValue> {
+        match self {
+            AttributeMeta::NameValue { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The entries of a list
+    pub fn items(&self) -> &'static [AttributeMeta] {
+        match self {
+            AttributeMeta::List { items, .. } => items,
+            _ => &[],
+        }
+    }
+}
+
+/// Queries over the attributes of a field, variant or container, as found in
+/// e.g. [`FieldInfo::metadata`](crate::FieldInfo::metadata)
+///
+/// Each lookup takes the attribute's namespace, e.g. `"visit"` for
+/// `#[visit(sensitive, max_len = 64)]`, and searches the entries of every such
+/// attribute in order, returning the first match.
+///
+/// ```
+/// use visit_rs::metadata::{AttributeMeta, MetaQuery, MetaValue};
+///
+/// // #[visit(sensitive, max_len = 64)]
+/// let metadata = [AttributeMeta::List {
+///     path: "visit",
+///     items: &[
+///         AttributeMeta::Path { path: "sensitive" },
+///         AttributeMeta::NameValue {
+///             path: "max_len",
+///             name: "max_len",
+///             value: MetaValue::Int(64),
+///         },
+///     ],
+/// }];
+/// assert!(metadata.has_flag("visit", "sensitive"));
+/// assert_eq!(metadata.get_int("visit", "max_len"), Some(64));
+/// assert_eq!(metadata.get_str("serde", "rename"), None);
+/// ```
+pub trait MetaQuery {
+    /// The entries of every `#[namespace(...)]` attribute, in order
+    fn namespace<'a>(
+        &'a self,
+        namespace: &'a str,
+    ) -> impl Iterator<Item = &'static AttributeMeta> + 'a;
+
+    /// The first entry named `name`, whether a flag, name-value pair or list
+    fn entry(&self, namespace: &str, name: &str) -> Option<&'static AttributeMeta> {
+        self.namespace(namespace).find(|meta| meta.name() == name)
+    }
+
+    /// The value of the first `name = value` entry. Named so as not to be shadowed
+    /// by the slice's own `get`
+    fn get_value(&self, namespace: &str, name: &str) -> Option<&'static MetaValue> {
+        self.namespace(namespace)
+            .filter(|meta| meta.name() == name)
+            .find_map(AttributeMeta::value)
+    }
+
+    /// Whether there is a bare `name` entry, e.g. `#[visit(sensitive)]`
+    fn has_flag(&self, namespace: &str, name: &str) -> bool {
+        self.namespace(namespace)
+            .any(|meta| matches!(meta, AttributeMeta::Path { path } if *path == name))
+    }
+
+    /// The entries of the first `name(...)` entry, e.g. `bound(visit = "...")`
+    fn get_list(&self, namespace: &str, name: &str) -> Option<&'static [AttributeMeta]> {
+        self.namespace(namespace).find_map(|meta| match meta {
+            AttributeMeta::List { path, items } if *path == name => Some(*items),
+            _ => None,
+        })
+    }
+
+    /// The value of the first `name = "..."` entry, if it is a string
+    fn get_str(&self, namespace: &str, name: &str) -> Option<&'static str> {
+        self.get_value(namespace, name).and_then(MetaValue::as_str)
+    }
+
+    /// The value of the first `name = ...` entry, if it is a boolean
+    fn get_bool(&self, namespace: &str, name: &str) -> Option<bool> {
+        self.get_value(namespace, name).and_then(MetaValue::as_bool)
+    }
+
+    /// The value of the first `name = ...` entry, if it is an integer
+    fn get_int(&self, namespace: &str, name: &str) -> Option<i64> {
+        self.get_value(namespace, name).and_then(MetaValue::as_int)
+    }
+}
+
+impl MetaQuery for [AttributeMeta] {
+    fn namespace<'a>(
+        &'a self,
+        namespace: &'a str,
+    ) -> impl Iterator<Item = &'static AttributeMeta> + 'a {
+        self.iter()
+            .filter(move |meta| meta.name() == namespace)
+            .flat_map(AttributeMeta::items)
+    }
+}
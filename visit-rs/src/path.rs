@@ -0,0 +1,113 @@
+//! Tracking where a recursive visit currently is
+//!
+//! A [`PathVisitor`] owns a [`VisitPath`]. The derived [`VisitFieldsPathed`] and
+//! [`VisitVariantFieldsPathed`] impls push a segment before visiting each field
+//! and pop it afterwards, so the visitor can read its location at any depth,
+//! e.g. to report `config.servers[2].port` in an error.
+
+use std::fmt;
+
+use crate::{EnumInfo, StructInfo, UntilBreak, Visit, Visitor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A named field, by its renamed name
+    Field(&'static str),
+    /// A tuple field, by its declaration index
+    TupleIndex(usize),
+    /// An enum variant, by its renamed name
+    Variant(&'static str),
+    /// An element of a sequence
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct VisitPath {
+    segments: Vec<PathSegment>,
+}
+impl VisitPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+/// Renders the path as `config.servers[2].port`, with variants as `::Name`
+impl fmt::Display for VisitPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if idx == 0 => write!(f, "{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::TupleIndex(index) if idx == 0 => write!(f, "{index}")?,
+                PathSegment::TupleIndex(index) => write!(f, ".{index}")?,
+                PathSegment::Variant(name) => write!(f, "::{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+pub trait PathVisitor: Visitor + Sized {
+    fn path(&self) -> &VisitPath;
+    fn path_mut(&mut self) -> &mut VisitPath;
+    /// Visits `value` with `segment` pushed onto the path
+    fn visit_at<T: Visit<Self> + ?Sized>(
+        &mut self,
+        segment: PathSegment,
+        value: &T,
+    ) -> Self::Result {
+        self.path_mut().push(segment);
+        let res = value.visit(self);
+        self.path_mut().pop();
+        res
+    }
+    /// Visits each element of a sequence with its index pushed onto the path
+    fn visit_seq_at<'a, T, I>(&'a mut self, items: I) -> impl Iterator<Item = Self::Result> + 'a
+    where
+        T: Visit<Self> + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+        I::IntoIter: 'a,
+    {
+        UntilBreak::<Self, _>::new(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| self.visit_at(PathSegment::Index(index), item)),
+        )
+    }
+}
+
+/// Like [`VisitFieldsNamed`](crate::VisitFieldsNamed), with each field's segment
+/// pushed onto the visitor's path while it is visited
+pub trait VisitFieldsPathed<V: PathVisitor>: StructInfo {
+    fn visit_fields_pathed<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
+
+/// Like [`VisitVariantFieldsNamed`](crate::VisitVariantFieldsNamed), with the
+/// variant's and each field's segments pushed onto the visitor's path while it is
+/// visited
+pub trait VisitVariantFieldsPathed<V: PathVisitor>: EnumInfo {
+    fn visit_variant_fields_pathed<'a>(
+        &'a self,
+        visitor: &'a mut V,
+    ) -> impl Iterator<Item = V::Result> + 'a;
+}
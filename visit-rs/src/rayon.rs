@@ -0,0 +1,30 @@
+//! Visiting fields in parallel on the rayon thread pool
+//!
+//! Each rayon worker visits with its own clone of the visitor, so the visitor must
+//! be `Clone + Send`. The returned iterators are indexed, so `collect` yields the
+//! results in field order, and `reduce`/`sum` can combine them without collecting.
+//! [`Visitor::is_break`] is not consulted; use e.g. `find_any` to stop early.
+
+use rayon::iter::IndexedParallelIterator;
+
+use crate::{StructInfo, Visitor};
+
+pub trait ParVisitFields<V: Visitor>: StructInfo {
+    fn par_visit_fields<'a>(
+        &'a self,
+        visitor: &V,
+    ) -> impl IndexedParallelIterator<Item = V::Result> + 'a
+    where
+        V: Clone + Send + 'a,
+        V::Result: Send;
+}
+
+pub trait ParVisitFieldsNamed<V: Visitor>: StructInfo {
+    fn par_visit_fields_named<'a>(
+        &'a self,
+        visitor: &V,
+    ) -> impl IndexedParallelIterator<Item = V::Result> + 'a
+    where
+        V: Clone + Send + 'a,
+        V::Result: Send;
+}
@@ -0,0 +1,353 @@
+//! Structural hooks for standard containers and smart pointers
+//!
+//! A [`StructuralVisitor`] receives options, sequences, maps and tuples through
+//! its hooks instead of through a `Visit` impl per concrete container type.
+//! Because the `serde` integration implements `Visit` for every `Serialize` type,
+//! the std impls cannot be blanket impls; instead a visitor opts in with
+//! [`impl_structural_visit!`](crate::impl_structural_visit), which implements
+//! `Visit` for the std types (and their `Static` counterparts) for that visitor.
+//!
+//! Smart pointers (`Rc`, `Arc`, `Cow`) are transparent and visit their pointee
+//! directly. `Box<T>` and `&T` are fundamental types, so the orphan rules only
+//! allow their `Static` counterparts to be implemented this way.
+//!
+//! For `Static` containers there are no values to walk: options pass
+//! `Some(&Static<T>)`, tuples yield one `Static` per element type, and
+//! sequences and maps yield a single `Static` element (or entry) standing in for
+//! all of them, with no size hint.
+
+use crate::{Static, Visit, Visitor};
+
+pub trait StructuralVisitor: Visitor + Sized {
+    fn visit_option<T: Visit<Self> + ?Sized>(&mut self, value: Option<&T>) -> Self::Result;
+    fn visit_seq<S: SeqAccess<Self>>(&mut self, seq: S) -> Self::Result;
+    fn visit_map<M: MapAccess<Self>>(&mut self, map: M) -> Self::Result;
+    fn visit_tuple<S: SeqAccess<Self>>(&mut self, tuple: S) -> Self::Result {
+        self.visit_seq(tuple)
+    }
+}
+
+/// Visits the elements of a sequence or tuple one at a time
+pub trait SeqAccess<V: Visitor> {
+    /// The number of remaining elements, if known
+    fn size_hint(&self) -> Option<usize>;
+    /// Visits the next element, or returns `None` once all have been visited
+    fn visit_next(&mut self, visitor: &mut V) -> Option<V::Result>;
+}
+
+/// Visits the entries of a map one key and value at a time
+pub trait MapAccess<V: Visitor> {
+    /// The number of remaining entries, if known
+    fn size_hint(&self) -> Option<usize>;
+    /// Visits the key of the next entry, or returns `None` once all have been visited
+    fn visit_next_key(&mut self, visitor: &mut V) -> Option<V::Result>;
+    /// Visits the value of the entry whose key was just visited
+    ///
+    /// Panics if there is no such entry
+    fn visit_next_value(&mut self, visitor: &mut V) -> V::Result;
+}
+
+/// [`SeqAccess`] over an iterator of references
+pub struct Seq<I> {
+    iter: I,
+    size_hint: Option<usize>,
+}
+impl<I: Iterator> Seq<I> {
+    pub fn new(iter: I) -> Self {
+        let size_hint = match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        Self { iter, size_hint }
+    }
+    pub fn with_size_hint(iter: I, size_hint: Option<usize>) -> Self {
+        Self { iter, size_hint }
+    }
+}
+impl<'a, V, T, I> SeqAccess<V> for Seq<I>
+where
+    V: Visitor,
+    T: Visit<V> + ?Sized + 'a,
+    I: Iterator<Item = &'a T>,
+{
+    fn size_hint(&self) -> Option<usize> {
+        self.size_hint
+    }
+    fn visit_next(&mut self, visitor: &mut V) -> Option<V::Result> {
+        let item = self.iter.next()?;
+        self.size_hint = self.size_hint.map(|n| n.saturating_sub(1));
+        Some(item.visit(visitor))
+    }
+}
+
+/// [`MapAccess`] over an iterator of key-value reference pairs
+pub struct Entries<'a, I, W: ?Sized> {
+    iter: I,
+    size_hint: Option<usize>,
+    value: Option<&'a W>,
+}
+impl<'a, I: Iterator, W: ?Sized> Entries<'a, I, W> {
+    pub fn new(iter: I) -> Self {
+        let size_hint = match iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        Self::with_size_hint(iter, size_hint)
+    }
+    pub fn with_size_hint(iter: I, size_hint: Option<usize>) -> Self {
+        Self {
+            iter,
+            size_hint,
+            value: None,
+        }
+    }
+}
+impl<'a, V, K, W, I> MapAccess<V> for Entries<'a, I, W>
+where
+    V: Visitor,
+    K: Visit<V> + ?Sized + 'a,
+    W: Visit<V> + ?Sized + 'a,
+    I: Iterator<Item = (&'a K, &'a W)>,
+{
+    fn size_hint(&self) -> Option<usize> {
+        self.size_hint
+    }
+    fn visit_next_key(&mut self, visitor: &mut V) -> Option<V::Result> {
+        let (key, value) = self.iter.next()?;
+        self.size_hint = self.size_hint.map(|n| n.saturating_sub(1));
+        self.value = Some(value);
+        Some(key.visit(visitor))
+    }
+    fn visit_next_value(&mut self, visitor: &mut V) -> V::Result {
+        self.value
+            .take()
+            .expect("visit_next_value called without a key")
+            .visit(visitor)
+    }
+}
+
+/// [`SeqAccess`] over the elements of a tuple, or of a `Static` tuple type
+pub struct Tuple<'a, T: ?Sized> {
+    tuple: &'a T,
+    pos: usize,
+}
+impl<'a, T: ?Sized> Tuple<'a, T> {
+    pub fn new(tuple: &'a T) -> Self {
+        Self { tuple, pos: 0 }
+    }
+}
+
+macro_rules! tuple_access {
+    ($len:literal; $($ty:ident $idx:tt),+) => {
+        impl<'a, V: Visitor, $($ty: Visit<V>),+> SeqAccess<V> for Tuple<'a, ($($ty,)+)> {
+            fn size_hint(&self) -> Option<usize> {
+                Some($len - self.pos)
+            }
+            fn visit_next(&mut self, visitor: &mut V) -> Option<V::Result> {
+                let res = match self.pos {
+                    $($idx => self.tuple.$idx.visit(visitor),)+
+                    _ => return None,
+                };
+                self.pos += 1;
+                Some(res)
+            }
+        }
+
+        impl<'a, V: Visitor, $($ty),+> SeqAccess<V> for Tuple<'a, Static<($($ty,)+)>>
+        where
+            $(Static<$ty>: Visit<V>,)+
+        {
+            fn size_hint(&self) -> Option<usize> {
+                Some($len - self.pos)
+            }
+            fn visit_next(&mut self, visitor: &mut V) -> Option<V::Result> {
+                let res = match self.pos {
+                    $($idx => Static::<$ty>::new().visit(visitor),)+
+                    _ => return None,
+                };
+                self.pos += 1;
+                Some(res)
+            }
+        }
+    };
+}
+
+tuple_access!(1; T0 0);
+tuple_access!(2; T0 0, T1 1);
+tuple_access!(3; T0 0, T1 1, T2 2);
+tuple_access!(4; T0 0, T1 1, T2 2, T3 3);
+tuple_access!(5; T0 0, T1 1, T2 2, T3 3, T4 4);
+tuple_access!(6; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+tuple_access!(7; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+tuple_access!(8; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+tuple_access!(9; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8);
+tuple_access!(10; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9);
+tuple_access!(11; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10);
+tuple_access!(12; T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9, T10 10, T11 11);
+
+/// Implements `Visit` for the std containers and smart pointers, and their
+/// `Static` counterparts, dispatching to the hooks of a [`StructuralVisitor`]
+///
+/// ```ignore
+/// visit_rs::impl_structural_visit!(MyVisitor);
+/// visit_rs::impl_structural_visit!(impl<W: std::fmt::Write> MyWriter<W>);
+/// ```
+#[macro_export]
+macro_rules! impl_structural_visit {
+    (impl<$($gen:ident $(: $bound:path)?),* $(,)?> $visitor:ty) => {
+        $crate::__impl_structural_visit!([$($gen $(: $bound)?,)*] $visitor);
+    };
+    ($visitor:ty) => {
+        $crate::__impl_structural_visit!([] $visitor);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_structural_visit {
+    ([$($gen:tt)*] $visitor:ty) => {
+        impl<__T: $crate::Visit<$visitor>, $($gen)*> $crate::Visit<$visitor> for ::std::option::Option<__T> {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_option(visitor, self.as_ref())
+            }
+        }
+        impl<__T, $($gen)*> $crate::Visit<$visitor> for $crate::Static<::std::option::Option<__T>>
+        where
+            $crate::Static<__T>: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_option(
+                    visitor,
+                    Some(&$crate::Static::<__T>::new()),
+                )
+            }
+        }
+
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T] ::std::vec::Vec<__T>);
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T] [__T]);
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T, const __N: usize] [__T; __N]);
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T] ::std::collections::VecDeque<__T>);
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T] ::std::collections::BTreeSet<__T>);
+        $crate::__impl_structural_visit!(@seq [$($gen)*] $visitor; [__T, __S] ::std::collections::HashSet<__T, __S>);
+        $crate::__impl_structural_visit!(@map [$($gen)*] $visitor; [__K, __W] ::std::collections::BTreeMap<__K, __W>);
+        $crate::__impl_structural_visit!(@map [$($gen)*] $visitor; [__K, __W, __S] ::std::collections::HashMap<__K, __W, __S>);
+
+        $crate::__impl_structural_visit!(@deref_static [$($gen)*] $visitor; [__T: ?Sized] __T; &__T);
+        $crate::__impl_structural_visit!(@deref_static [$($gen)*] $visitor; [__T: ?Sized] __T; ::std::boxed::Box<__T>);
+        $crate::__impl_structural_visit!(@deref [$($gen)*] $visitor; [__T: ?Sized] __T; ::std::rc::Rc<__T>);
+        $crate::__impl_structural_visit!(@deref [$($gen)*] $visitor; [__T: ?Sized] __T; ::std::sync::Arc<__T>);
+        $crate::__impl_structural_visit!(@deref [$($gen)*] $visitor; [__T: ?Sized + ::std::borrow::ToOwned] __T; ::std::borrow::Cow<'_, __T>);
+
+        $crate::__impl_structural_visit!(@tuple [$($gen)*] $visitor; __T0 __T1 __T2 __T3 __T4 __T5 __T6 __T7 __T8 __T9 __T10 __T11);
+    };
+    (@seq [$($gen:tt)*] $visitor:ty; [$($params:tt)*] $ty:ty) => {
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $ty
+        where
+            __T: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_seq(
+                    visitor,
+                    $crate::structural::Seq::new(self.iter()),
+                )
+            }
+        }
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $crate::Static<$ty>
+        where
+            $crate::Static<__T>: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                let element = $crate::Static::<__T>::new();
+                $crate::structural::StructuralVisitor::visit_seq(
+                    visitor,
+                    $crate::structural::Seq::with_size_hint(::std::iter::once(&element), None),
+                )
+            }
+        }
+    };
+    (@map [$($gen:tt)*] $visitor:ty; [$($params:tt)*] $ty:ty) => {
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $ty
+        where
+            __K: $crate::Visit<$visitor>,
+            __W: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_map(
+                    visitor,
+                    $crate::structural::Entries::new(self.iter()),
+                )
+            }
+        }
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $crate::Static<$ty>
+        where
+            $crate::Static<__K>: $crate::Visit<$visitor>,
+            $crate::Static<__W>: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                let key = $crate::Static::<__K>::new();
+                let value = $crate::Static::<__W>::new();
+                $crate::structural::StructuralVisitor::visit_map(
+                    visitor,
+                    $crate::structural::Entries::with_size_hint(
+                        ::std::iter::once((&key, &value)),
+                        None,
+                    ),
+                )
+            }
+        }
+    };
+    (@deref [$($gen:tt)*] $visitor:ty; [$($params:tt)*] $inner:ty; $ty:ty) => {
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $ty
+        where
+            $inner: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                <$inner as $crate::Visit<$visitor>>::visit(&**self, visitor)
+            }
+        }
+        $crate::__impl_structural_visit!(@deref_static [$($gen)*] $visitor; [$($params)*] $inner; $ty);
+    };
+    (@deref_static [$($gen:tt)*] $visitor:ty; [$($params:tt)*] $inner:ty; $ty:ty) => {
+        impl<$($params)*, $($gen)*> $crate::Visit<$visitor> for $crate::Static<$ty>
+        where
+            $crate::Static<$inner>: $crate::Visit<$visitor>,
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::Visit::visit(&$crate::Static::<$inner>::new(), visitor)
+            }
+        }
+    };
+    (@tuple [$($gen:tt)*] $visitor:ty;) => {};
+    (@tuple [$($gen:tt)*] $visitor:ty; $($ty:ident)+) => {
+        impl<$($ty: $crate::Visit<$visitor>,)+ $($gen)*> $crate::Visit<$visitor> for ($($ty,)+) {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_tuple(
+                    visitor,
+                    $crate::structural::Tuple::new(self),
+                )
+            }
+        }
+        impl<$($ty,)+ $($gen)*> $crate::Visit<$visitor> for $crate::Static<($($ty,)+)>
+        where
+            $($crate::Static<$ty>: $crate::Visit<$visitor>,)+
+        {
+            fn visit(&self, visitor: &mut $visitor) -> <$visitor as $crate::Visitor>::Result {
+                $crate::structural::StructuralVisitor::visit_tuple(
+                    visitor,
+                    $crate::structural::Tuple::new(self),
+                )
+            }
+        }
+        $crate::__impl_structural_visit!(@tuple_rest [$($gen)*] $visitor; $($ty)+);
+    };
+    (@tuple_rest [$($gen:tt)*] $visitor:ty; $last:ident) => {};
+    (@tuple_rest [$($gen:tt)*] $visitor:ty; $($ty:ident)+) => {
+        $crate::__impl_structural_visit!(@tuple_init [$($gen)*] $visitor; [] $($ty)+);
+    };
+    (@tuple_init [$($gen:tt)*] $visitor:ty; [$($init:ident)*] $last:ident) => {
+        $crate::__impl_structural_visit!(@tuple [$($gen)*] $visitor; $($init)*);
+    };
+    (@tuple_init [$($gen:tt)*] $visitor:ty; [$($init:ident)*] $next:ident $($rest:ident)+) => {
+        $crate::__impl_structural_visit!(@tuple_init [$($gen)*] $visitor; [$($init)* $next] $($rest)+);
+    };
+}
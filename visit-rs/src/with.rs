@@ -0,0 +1,154 @@
+//! Adapters for fields visited through user functions
+//!
+//! A field marked `#[visit(with = "module")]` is visited through functions in
+//! `module` rather than through its own `Visit` impls, which lets foreign types
+//! take part without a newtype:
+//!
+//! ```ignore
+//! mod rfc3339 {
+//!     pub fn visit<V: Visitor>(value: &DateTime<Utc>, visitor: &mut V) -> V::Result
+//!     where
+//!         String: Visit<V>,
+//!     { value.to_rfc3339().visit(visitor) }
+//!
+//!     pub fn visit_async<'a, V: Visitor>(
+//!         value: &'a DateTime<Utc>,
+//!         visitor: &'a mut V,
+//!     ) -> impl Future<Output = V::Result> + Send + 'a { ... }
+//!
+//!     pub fn visit_static<V: Visitor>(visitor: &mut V) -> V::Result { ... }
+//!
+//!     pub fn visit_static_async<'a, V: Visitor>(
+//!         visitor: &'a mut V,
+//!     ) -> impl Future<Output = V::Result> + Send + 'a { ... }
+//!
+//!     pub fn visit_mut<V: Visitor>(value: &mut DateTime<Utc>, visitor: &mut V) -> V::Result { ... }
+//!
+//!     pub fn visit_owned<V: Visitor>(value: DateTime<Utc>, visitor: &mut V) -> V::Result { ... }
+//! }
+//! ```
+//!
+//! `#[visit(visit_with = "path::to::fn")]` supplies only `visit`, leaving async,
+//! static, mutable and owned visits of the field as they are.
+//!
+//! The derive hands the visitor a [`VisitWith`] (or its async, mutable or owned
+//! counterpart) in place of the field, wrapped in [`Named`](crate::Named) or
+//! [`Covered`](crate::Covered) as usual, so `Named` impls that delegate to their value keep working. Bounds the
+//! functions need are not inferred; add them with `#[visit(bound = "...")]`.
+//!
+//! Mutable visits call `visit_mut(&mut T, &mut V)`, and owned visits, where the
+//! container derives them, `visit_owned(T, &mut V)`, so a mutable pass can change
+//! the field.
+//!
+//! Local visits await `visit_async` without requiring its future to be `Send`,
+//! taking the `visit_async` bound unless given their own, so a module whose
+//! `visit_async` asks nothing of the visitor serves `Rc` visitors too.
+
+use futures::future::{BoxFuture, LocalBoxFuture};
+
+use crate::{Visit, VisitAsync, VisitAsyncLocal, VisitMut, VisitOwned, Visitor};
+
+/// A value visited by calling `visit` on it
+pub struct VisitWith<'a, T: ?Sized, V: Visitor> {
+    value: &'a T,
+    visit: fn(&T, &mut V) -> V::Result,
+}
+impl<'a, T: ?Sized, V: Visitor> VisitWith<'a, T, V> {
+    pub fn new(value: &'a T, visit: fn(&T, &mut V) -> V::Result) -> Self {
+        Self { value, visit }
+    }
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+}
+impl<T: ?Sized, V: Visitor> Visit<V> for VisitWith<'_, T, V> {
+    fn visit(&self, visitor: &mut V) -> V::Result {
+        (self.visit)(self.value, visitor)
+    }
+}
+
+/// A value visited by calling `visit_mut` on it
+pub struct VisitMutWith<'a, T: ?Sized, V: Visitor> {
+    value: &'a mut T,
+    visit: fn(&mut T, &mut V) -> V::Result,
+}
+impl<'a, T: ?Sized, V: Visitor> VisitMutWith<'a, T, V> {
+    pub fn new(value: &'a mut T, visit: fn(&mut T, &mut V) -> V::Result) -> Self {
+        Self { value, visit }
+    }
+    pub fn value(&mut self) -> &mut T {
+        self.value
+    }
+}
+impl<T: ?Sized, V: Visitor> VisitMut<V> for VisitMutWith<'_, T, V> {
+    fn visit_mut(&mut self, visitor: &mut V) -> V::Result {
+        (self.visit)(self.value, visitor)
+    }
+}
+
+/// A value visited by passing it to `visit_owned`
+pub struct VisitOwnedWith<T, V: Visitor> {
+    value: T,
+    visit: fn(T, &mut V) -> V::Result,
+}
+impl<T, V: Visitor> VisitOwnedWith<T, V> {
+    pub fn new(value: T, visit: fn(T, &mut V) -> V::Result) -> Self {
+        Self { value, visit }
+    }
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+impl<T, V: Visitor> VisitOwned<V> for VisitOwnedWith<T, V> {
+    fn visit_owned(self, visitor: &mut V) -> V::Result {
+        (self.visit)(self.value, visitor)
+    }
+}
+
+/// A value visited asynchronously by awaiting `visit` on it
+pub struct VisitAsyncWith<'a, T: ?Sized, V: Visitor> {
+    value: &'a T,
+    visit: for<'b> fn(&'b T, &'b mut V) -> BoxFuture<'b, V::Result>,
+}
+impl<'a, T: ?Sized, V: Visitor> VisitAsyncWith<'a, T, V> {
+    pub fn new(
+        value: &'a T,
+        visit: for<'b> fn(&'b T, &'b mut V) -> BoxFuture<'b, V::Result>,
+    ) -> Self {
+        Self { value, visit }
+    }
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+}
+impl<T: ?Sized, V: Visitor> VisitAsync<V> for VisitAsyncWith<'_, T, V> {
+    fn visit_async<'a>(&'a self, visitor: &'a mut V) -> impl Future<Output = V::Result> + Send + 'a
+    where
+        V: Send,
+        V::Result: Send,
+    {
+        (self.visit)(self.value, visitor)
+    }
+}
+
+/// A value visited on the current thread by awaiting `visit` on it
+pub struct VisitAsyncLocalWith<'a, T: ?Sized, V: Visitor> {
+    value: &'a T,
+    visit: for<'b> fn(&'b T, &'b mut V) -> LocalBoxFuture<'b, V::Result>,
+}
+impl<'a, T: ?Sized, V: Visitor> VisitAsyncLocalWith<'a, T, V> {
+    pub fn new(
+        value: &'a T,
+        visit: for<'b> fn(&'b T, &'b mut V) -> LocalBoxFuture<'b, V::Result>,
+    ) -> Self {
+        Self { value, visit }
+    }
+    pub fn value(&self) -> &'a T {
+        self.value
+    }
+}
+impl<T: ?Sized, V: Visitor> VisitAsyncLocal<V> for VisitAsyncLocalWith<'_, T, V> {
+    fn visit_async_local<'a>(&'a self, visitor: &'a mut V) -> impl Future<Output = V::Result> + 'a {
+        (self.visit)(self.value, visitor)
+    }
+}
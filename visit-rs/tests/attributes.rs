@@ -1,5 +1,7 @@
 #[cfg(feature = "meta")]
-use visit_rs::{StructInfo, VisitFields, Visitor, metadata::AttributeMeta};
+use visit_rs::metadata::{AttributeMeta, MetaQuery};
+#[cfg(feature = "meta")]
+use visit_rs::{StructInfo, VisitFields, Visitor};
 
 #[cfg(feature = "meta")]
 #[derive(VisitFields)]
@@ -23,7 +25,7 @@ impl Visitor for MetadataCollector {
 
 #[cfg(feature = "meta")]
 impl<'a, T> visit_rs::Visit<MetadataCollector> for visit_rs::Named<'a, T> {
-    fn visit(&self, visitor: &mut MetadataCollector) -> () {
+    fn visit(&self, visitor: &mut MetadataCollector) {
         visitor.metadata.push(self.metadata);
     }
 }
@@ -108,3 +110,93 @@ fn test_field_metadata() {
     // field2 should have no attributes
     assert_eq!(visitor.metadata[1].len(), 0);
 }
+
+#[cfg(feature = "meta")]
+#[derive(VisitFields)]
+#[visit(rename_all = "camelCase", audited)]
+struct SignupForm {
+    #[visit(sensitive)]
+    #[visit(max_len = 64, label = "Password")]
+    password: String,
+    #[visit(max_len = 254, trim = true, weight = 0.5)]
+    #[visit(check(format = "email", deny = "example.com"))]
+    email: String,
+    #[visit(default = Self::default_source)]
+    source: String,
+}
+
+#[cfg(feature = "meta")]
+#[test]
+fn test_metadata_query() {
+    assert!(SignupForm::DATA.metadata.has_flag("visit", "audited"));
+    assert_eq!(
+        SignupForm::DATA.metadata.get_str("visit", "rename_all"),
+        Some("camelCase")
+    );
+
+    let [password, email, source] = SignupForm::FIELDS else {
+        panic!("Expected three fields");
+    };
+    assert!(password.metadata.has_flag("visit", "sensitive"));
+    assert!(!email.metadata.has_flag("visit", "sensitive"));
+    assert!(!password.metadata.has_flag("serde", "sensitive"));
+    assert_eq!(password.metadata.get_int("visit", "max_len"), Some(64));
+    assert_eq!(
+        password.metadata.get_str("visit", "label"),
+        Some("Password")
+    );
+    assert_eq!(password.metadata.get_str("visit", "max_len"), None);
+    assert_eq!(
+        password
+            .metadata
+            .namespace("visit")
+            .map(AttributeMeta::name)
+            .collect::<Vec<_>>(),
+        vec!["sensitive", "max_len", "label"]
+    );
+
+    assert_eq!(email.metadata.get_int("visit", "max_len"), Some(254));
+    assert_eq!(email.metadata.get_bool("visit", "trim"), Some(true));
+    assert_eq!(
+        email
+            .metadata
+            .get_value("visit", "weight")
+            .and_then(|value| value.as_f64()),
+        Some(0.5)
+    );
+    let check = email.metadata.get_list("visit", "check").unwrap();
+    assert_eq!(
+        check[0].value().and_then(|value| value.as_str()),
+        Some("email")
+    );
+    assert_eq!(check[1].name(), "deny");
+
+    assert_eq!(
+        source
+            .metadata
+            .get_value("visit", "default")
+            .and_then(|value| value.as_path()),
+        Some("Self :: default_source")
+    );
+}
+
+/// Custom keys near a built-in one are metadata, as they carry a value or the
+/// built-in key does not apply to fields
+#[cfg(feature = "meta")]
+#[derive(VisitFields)]
+struct Column {
+    #[visit(owner = "team-a", width = 3)]
+    #[visit(owner)]
+    name: String,
+}
+
+#[cfg(feature = "meta")]
+#[test]
+fn test_metadata_near_keys() {
+    let [name] = Column::FIELDS else {
+        panic!("Expected one field");
+    };
+    assert_eq!(name.metadata.get_str("visit", "owner"), Some("team-a"));
+    assert_eq!(name.metadata.get_int("visit", "width"), Some(3));
+    assert!(name.metadata.has_flag("visit", "owner"));
+}
@@ -0,0 +1,113 @@
+use visit_rs::dynamic::{DynVisit, DynVisitor};
+use visit_rs::{Visit, VisitFields, VisitVariantFields, VisitVariants, Visitor};
+
+/// A container visiting its elements in turn, like the std impls would
+struct List<T>(Vec<T>);
+
+impl<T: DynVisit> DynVisit for List<T> {
+    fn dyn_visit(&self, visitor: &mut dyn DynVisitor) {
+        for item in &self.0 {
+            item.dyn_visit(visitor);
+        }
+    }
+}
+
+/// Without the field bound, `Node: DynVisit` requires `List<Node>: DynVisit`,
+/// which requires `Node: DynVisit` again
+#[derive(VisitFields)]
+struct Node {
+    name: String,
+    #[visit(bound(dyn_visit = ""))]
+    children: List<Node>,
+}
+
+fn node(name: &str, children: Vec<Node>) -> Node {
+    Node {
+        name: name.to_string(),
+        children: List(children),
+    }
+}
+
+/// Collects every string it is handed
+#[derive(Default)]
+struct Strings(Vec<String>);
+
+impl DynVisitor for Strings {
+    fn visit_bool(&mut self, _value: bool) {}
+    fn visit_i64(&mut self, _value: i64) {}
+    fn visit_u64(&mut self, _value: u64) {}
+    fn visit_f64(&mut self, _value: f64) {}
+    fn visit_str(&mut self, value: &str) {
+        self.0.push(value.to_string());
+    }
+}
+
+#[test]
+fn test_recursive_field_bound() {
+    let tree = node(
+        "root",
+        vec![
+            node("a", vec![node("a1", Vec::new())]),
+            node("b", Vec::new()),
+        ],
+    );
+    let mut strings = Strings::default();
+    tree.dyn_visit(&mut strings);
+    assert_eq!(strings.0, vec!["root", "a", "a1", "b"]);
+}
+
+/// The container bound replaces every inferred predicate, including the
+/// recursive one
+#[derive(VisitFields)]
+#[visit(bound(dyn_visit = "T: visit_rs::dynamic::DynVisit"))]
+struct Tree<T> {
+    value: T,
+    children: List<Tree<T>>,
+}
+
+#[test]
+fn test_generic_container_bound() {
+    let tree = Tree {
+        value: "root",
+        children: List(vec![Tree {
+            value: "leaf",
+            children: List(Vec::new()),
+        }]),
+    };
+    let mut strings = Strings::default();
+    tree.dyn_visit(&mut strings);
+    assert_eq!(strings.0, vec!["root", "leaf"]);
+}
+
+/// Counts the values it visits
+struct Count;
+
+impl Visitor for Count {
+    type Result = usize;
+}
+
+impl<T> Visit<Count> for Vec<T> {
+    fn visit(&self, _visitor: &mut Count) -> usize {
+        self.len()
+    }
+}
+
+/// Generic enums get their impl generics merged with the visitor's, where clause
+/// included. `VisitVariant` bounds `Variant<'a, Self>` for every `'a`, so the
+/// parameters must be `'static`
+#[derive(VisitVariants)]
+enum Either<L: 'static, R: 'static>
+where
+    R: Clone,
+{
+    Left(L),
+    Right(R),
+}
+
+#[test]
+fn test_generic_enum() {
+    let left: Either<Vec<u8>, Vec<char>> = Either::Left(vec![1, 2]);
+    assert_eq!(left.visit_variant_fields(&mut Count).sum::<usize>(), 2);
+    let right: Either<Vec<u8>, Vec<char>> = Either::Right(vec!['a']);
+    assert_eq!(right.visit_variant_fields(&mut Count).sum::<usize>(), 1);
+}
@@ -0,0 +1,168 @@
+#![allow(clippy::manual_async_fn)]
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use futures::StreamExt;
+use visit_rs::{
+    ForkVisitor, Named, VisitAsync, VisitFields, VisitFieldsConcurrent, VisitFieldsNamedConcurrent,
+    VisitVariantFieldsConcurrent, VisitVariants, Visitor,
+};
+
+/// A remote dependency that takes `latency_ms` to answer a health check
+struct Dependency {
+    latency_ms: u64,
+}
+
+/// Checks dependencies, counting how many forks were handed out
+#[derive(Default)]
+struct Probe {
+    forks: Arc<AtomicUsize>,
+}
+
+impl Visitor for Probe {
+    type Result = String;
+}
+
+impl ForkVisitor for Probe {
+    fn fork(&self) -> Self {
+        self.forks.fetch_add(1, Ordering::SeqCst);
+        Probe {
+            forks: self.forks.clone(),
+        }
+    }
+}
+
+impl VisitAsync<Probe> for Dependency {
+    fn visit_async<'a>(
+        &'a self,
+        _visitor: &'a mut Probe,
+    ) -> impl Future<Output = String> + Send + 'a {
+        async move {
+            tokio::time::sleep(Duration::from_millis(self.latency_ms)).await;
+            format!("{}ms", self.latency_ms)
+        }
+    }
+}
+
+impl<'a> VisitAsync<Probe> for Named<'a, Dependency> {
+    fn visit_async<'b>(
+        &'b self,
+        visitor: &'b mut Probe,
+    ) -> impl Future<Output = String> + Send + 'b {
+        async move {
+            let res = self.value.visit_async(visitor).await;
+            format!("{}: {res}", self.name.unwrap_or("?"))
+        }
+    }
+}
+
+#[derive(VisitFields)]
+struct Health {
+    database: Dependency,
+    #[visit(skip)]
+    _region: String,
+    queue: Dependency,
+    storage: Dependency,
+}
+
+#[derive(VisitVariants)]
+enum Target {
+    Single(Dependency),
+    Replicated {
+        primary: Dependency,
+        replica: Dependency,
+    },
+    Offline,
+}
+
+fn health() -> Health {
+    Health {
+        database: Dependency { latency_ms: 150 },
+        _region: "eu".to_string(),
+        queue: Dependency { latency_ms: 50 },
+        storage: Dependency { latency_ms: 100 },
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_visit_fields_concurrent() {
+    let health = health();
+    let probe = Probe::default();
+
+    // Run together, the checks take as long as the slowest
+    let start = tokio::time::Instant::now();
+    let results: Vec<_> = health.visit_fields_concurrent(&probe, 3).collect().await;
+    assert_eq!(start.elapsed(), Duration::from_millis(150));
+    assert_eq!(
+        results,
+        vec![
+            (2, "50ms".to_string()),
+            (3, "100ms".to_string()),
+            (0, "150ms".to_string()),
+        ]
+    );
+    assert_eq!(probe.forks.load(Ordering::SeqCst), 3);
+
+    // With a limit of one, fields are visited in declaration order, one after another
+    let start = tokio::time::Instant::now();
+    let results: Vec<_> = health.visit_fields_concurrent(&probe, 1).collect().await;
+    assert_eq!(start.elapsed(), Duration::from_millis(300));
+    let indices: Vec<_> = results.into_iter().map(|(index, _)| index).collect();
+    assert_eq!(indices, vec![0, 2, 3]);
+
+    // A limit of zero is taken as one
+    let results: Vec<_> = health.visit_fields_concurrent(&probe, 0).collect().await;
+    let indices: Vec<_> = results.into_iter().map(|(index, _)| index).collect();
+    assert_eq!(indices, vec![0, 2, 3]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_visit_fields_named_concurrent() {
+    let mut results: Vec<_> = health()
+        .visit_fields_named_concurrent(&Probe::default(), 2)
+        .collect()
+        .await;
+    results.sort();
+    assert_eq!(
+        results,
+        vec![
+            (0, "database: 150ms".to_string()),
+            (2, "queue: 50ms".to_string()),
+            (3, "storage: 100ms".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_visit_variant_fields_concurrent() {
+    let target = Target::Replicated {
+        primary: Dependency { latency_ms: 100 },
+        replica: Dependency { latency_ms: 10 },
+    };
+    let results: Vec<_> = target
+        .visit_variant_fields_concurrent(&Probe::default(), 2)
+        .collect()
+        .await;
+    assert_eq!(
+        results,
+        vec![(1, "10ms".to_string()), (0, "100ms".to_string())]
+    );
+
+    let single = Target::Single(Dependency { latency_ms: 1 });
+    let results: Vec<_> = single
+        .visit_variant_fields_concurrent(&Probe::default(), 2)
+        .collect()
+        .await;
+    assert_eq!(results, vec![(0, "1ms".to_string())]);
+
+    let probe = Probe::default();
+    let results: Vec<_> = Target::Offline
+        .visit_variant_fields_concurrent(&probe, 2)
+        .collect()
+        .await;
+    assert!(results.is_empty());
+    assert_eq!(probe.forks.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,170 @@
+#![allow(clippy::manual_async_fn)]
+
+use std::future::Future;
+use std::ops::ControlFlow;
+
+use futures::StreamExt;
+use visit_rs::{
+    Named, Visit, VisitAsync, VisitFields, VisitFieldsAsync, VisitFieldsNamed,
+    VisitFieldsNamedAsync, VisitVariantFields, VisitVariantFieldsNamed, VisitVariants, Visitor,
+};
+
+/// Rejects empty strings, stopping at the first one
+struct Validator {
+    visited: usize,
+}
+
+impl Visitor for Validator {
+    type Result = ControlFlow<String>;
+    fn is_break(result: &Self::Result) -> bool {
+        result.is_break()
+    }
+}
+
+impl Visit<Validator> for String {
+    fn visit(&self, visitor: &mut Validator) -> ControlFlow<String> {
+        visitor.visited += 1;
+        if self.is_empty() {
+            ControlFlow::Break("empty".to_string())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+impl VisitAsync<Validator> for String {
+    fn visit_async<'a>(
+        &'a self,
+        visitor: &'a mut Validator,
+    ) -> impl Future<Output = ControlFlow<String>> + Send + 'a {
+        async move { self.visit(visitor) }
+    }
+}
+
+impl<'a> Visit<Validator> for Named<'a, String> {
+    fn visit(&self, visitor: &mut Validator) -> ControlFlow<String> {
+        self.value
+            .visit(visitor)
+            .map_break(|reason| format!("{}: {reason}", self.name.unwrap_or("?")))
+    }
+}
+
+impl<'a> VisitAsync<Validator> for Named<'a, String> {
+    fn visit_async<'b>(
+        &'b self,
+        visitor: &'b mut Validator,
+    ) -> impl Future<Output = ControlFlow<String>> + Send + 'b {
+        async move { self.visit(visitor) }
+    }
+}
+
+/// A visitor that never overrides `is_break`
+struct Counter;
+
+impl Visitor for Counter {
+    type Result = Result<(), ()>;
+}
+
+impl Visit<Counter> for String {
+    fn visit(&self, _visitor: &mut Counter) -> Result<(), ()> {
+        if self.is_empty() { Err(()) } else { Ok(()) }
+    }
+}
+
+#[derive(VisitFields)]
+struct Form {
+    first: String,
+    second: String,
+    third: String,
+}
+
+#[derive(VisitVariants)]
+enum Input {
+    Pair(String, String),
+    Named { left: String, right: String },
+}
+
+fn form() -> Form {
+    Form {
+        first: "a".to_string(),
+        second: String::new(),
+        third: String::new(),
+    }
+}
+
+#[test]
+fn test_iterator_stops_after_break() {
+    let form = form();
+    let mut visitor = Validator { visited: 0 };
+    let results: Vec<_> = form.visit_fields(&mut visitor).collect();
+    assert_eq!(
+        results,
+        vec![
+            ControlFlow::Continue(()),
+            ControlFlow::Break("empty".to_string())
+        ]
+    );
+    assert_eq!(visitor.visited, 2);
+}
+
+#[test]
+fn test_try_visit_fields() {
+    let mut visitor = Validator { visited: 0 };
+    assert_eq!(
+        form().try_visit_fields_named(&mut visitor),
+        ControlFlow::Break(ControlFlow::Break("second: empty".to_string()))
+    );
+    assert_eq!(visitor.visited, 2);
+
+    let valid = Form {
+        first: "a".to_string(),
+        second: "b".to_string(),
+        third: "c".to_string(),
+    };
+    let mut visitor = Validator { visited: 0 };
+    assert_eq!(
+        valid.try_visit_fields(&mut visitor),
+        ControlFlow::Continue(())
+    );
+    assert_eq!(visitor.visited, 3);
+}
+
+#[test]
+fn test_default_never_breaks() {
+    let results: Vec<_> = form().visit_fields(&mut Counter).collect();
+    assert_eq!(results, vec![Ok(()), Err(()), Err(())]);
+}
+
+#[test]
+fn test_variant_fields_stop_after_break() {
+    let input = Input::Pair(String::new(), "b".to_string());
+    let mut visitor = Validator { visited: 0 };
+    assert_eq!(input.visit_variant_fields(&mut visitor).count(), 1);
+    assert_eq!(visitor.visited, 1);
+
+    let input = Input::Named {
+        left: "a".to_string(),
+        right: String::new(),
+    };
+    let mut visitor = Validator { visited: 0 };
+    assert_eq!(
+        input.try_visit_variant_fields_named(&mut visitor),
+        ControlFlow::Break(ControlFlow::Break("right: empty".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn test_stream_stops_after_break() {
+    let form = form();
+    let mut visitor = Validator { visited: 0 };
+    let results: Vec<_> = form.visit_fields_async(&mut visitor).collect().await;
+    assert_eq!(results.len(), 2);
+    assert_eq!(visitor.visited, 2);
+
+    let mut visitor = Validator { visited: 0 };
+    assert_eq!(
+        form.try_visit_fields_named_async(&mut visitor).await,
+        ControlFlow::Break(ControlFlow::Break("second: empty".to_string()))
+    );
+    assert_eq!(visitor.visited, 2);
+}
@@ -0,0 +1,61 @@
+/// A façade crate that re-exports visit-rs under another name
+mod facade {
+    pub use visit_rs as reexported;
+
+    pub mod prelude {
+        pub use visit_rs;
+    }
+}
+
+/// `visit_rs` is shadowed here, so the derive only works through `crate = "..."`
+mod shadowed {
+    #[allow(dead_code)]
+    mod visit_rs {}
+
+    use crate::facade::reexported::{StructInfo, VisitFields, VisitVariants};
+
+    #[derive(VisitFields)]
+    #[visit(crate = "crate::facade::reexported")]
+    pub struct Config {
+        pub name: String,
+    }
+
+    #[derive(VisitVariants)]
+    #[visit(crate = "crate::facade::reexported")]
+    pub enum Mode {
+        On,
+        Off,
+    }
+
+    #[test]
+    fn test_crate_attribute() {
+        assert_eq!(Config::DATA.name, "Config");
+        assert_eq!(Config::FIELDS[0].name, Some("name"));
+    }
+}
+
+/// Re-exporting visit-rs as `visit_rs` makes the derive work without the attribute
+mod via_prelude {
+    use crate::facade::prelude::*;
+    use visit_rs::{EnumInfo, VisitVariants};
+
+    #[derive(VisitVariants)]
+    enum Mode {
+        On,
+        Off,
+    }
+
+    #[test]
+    fn test_reexported_default() {
+        assert_eq!(Mode::On.variant_info().name, "On");
+        assert_eq!(Mode::Off.variant_info().name, "Off");
+    }
+}
+
+#[test]
+fn test_enum_crate_attribute() {
+    use visit_rs::EnumInfo;
+
+    assert_eq!(shadowed::Mode::Off.variant_info().name, "Off");
+    assert_eq!(shadowed::Mode::variants().into_iter().count(), 2);
+}
@@ -0,0 +1,166 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use visit_rs::dynamic::{DynVisit, DynVisitFields, DynVisitor};
+use visit_rs::{
+    EnumInfo, EnumInfoData, Named, StructInfoData, Variant, VisitFields, VisitVariants,
+};
+
+/// Writes `name=value` pairs, nesting structs and variants in brackets
+#[derive(Default)]
+struct KeyValueWriter(String);
+
+impl DynVisitor for KeyValueWriter {
+    fn visit_bool(&mut self, value: bool) {
+        write!(self.0, "{value}").unwrap();
+    }
+    fn visit_i64(&mut self, value: i64) {
+        write!(self.0, "{value}").unwrap();
+    }
+    fn visit_u64(&mut self, value: u64) {
+        write!(self.0, "{value}u").unwrap();
+    }
+    fn visit_f64(&mut self, value: f64) {
+        write!(self.0, "{value:.1}").unwrap();
+    }
+    fn visit_str(&mut self, value: &str) {
+        write!(self.0, "{value:?}").unwrap();
+    }
+    fn visit_field<'a>(&mut self, field: Named<'a, dyn DynVisit + 'a>) {
+        if let Some(name) = field.name {
+            write!(self.0, "{name}=").unwrap();
+        }
+        field.value.dyn_visit(self);
+        self.0.push(' ');
+    }
+    fn visit_struct<'a>(&mut self, info: &StructInfoData, fields: &'a (dyn DynVisitFields + 'a)) {
+        write!(self.0, "{}[ ", info.name).unwrap();
+        fields.dyn_visit_fields(self);
+        self.0.push(']');
+    }
+    fn visit_variant<'a>(
+        &mut self,
+        info: &EnumInfoData,
+        variant: Variant<'a, dyn DynVisitFields + 'a>,
+    ) {
+        write!(self.0, "{}::{}[ ", info.name, variant.info.name).unwrap();
+        variant.value.dyn_visit_fields(self);
+        self.0.push(']');
+    }
+}
+
+/// Counts leaves, relying on the provided methods to recurse
+#[derive(Default)]
+struct LeafCounter(usize);
+
+impl DynVisitor for LeafCounter {
+    fn visit_bool(&mut self, _value: bool) {
+        self.0 += 1;
+    }
+    fn visit_i64(&mut self, _value: i64) {
+        self.0 += 1;
+    }
+    fn visit_u64(&mut self, _value: u64) {
+        self.0 += 1;
+    }
+    fn visit_f64(&mut self, _value: f64) {
+        self.0 += 1;
+    }
+    fn visit_str(&mut self, _value: &str) {
+        self.0 += 1;
+    }
+}
+
+#[derive(VisitFields)]
+#[visit(rename_all = "camelCase")]
+struct Plugin {
+    plugin_name: String,
+    enabled: bool,
+    priority: i32,
+    limits: Limits,
+    mode: Mode,
+}
+
+#[derive(VisitFields)]
+struct Limits(u8, f32);
+
+#[derive(VisitVariants)]
+enum Mode {
+    Off,
+    Fixed(char),
+    Scaled { factor: f64, label: Box<str> },
+}
+
+/// Not every field implements `DynVisit`, so neither does this struct
+#[derive(VisitFields)]
+#[allow(dead_code)]
+struct Timed {
+    timeout: Duration,
+}
+
+fn plugin(mode: Mode) -> Plugin {
+    Plugin {
+        plugin_name: "resize".to_string(),
+        enabled: true,
+        priority: -2,
+        limits: Limits(4, 0.5),
+        mode,
+    }
+}
+
+fn run(visitor: &mut dyn DynVisitor, value: &dyn DynVisit) {
+    value.dyn_visit(visitor);
+}
+
+#[test]
+fn test_dyn_visit_struct() {
+    let mut writer = KeyValueWriter::default();
+    run(&mut writer, &plugin(Mode::Off));
+    assert_eq!(
+        writer.0,
+        r#"Plugin[ pluginName="resize" enabled=true priority=-2 limits=Limits[ 4u 0.5 ] mode=Mode::Off[ ] ]"#
+    );
+}
+
+#[test]
+fn test_dyn_visit_variants() {
+    let mut writer = KeyValueWriter::default();
+    run(&mut writer, &Mode::Fixed('x'));
+    assert_eq!(writer.0, r#"Mode::Fixed[ "x" ]"#);
+
+    let mut writer = KeyValueWriter::default();
+    let scaled = Mode::Scaled {
+        factor: 1.5,
+        label: "big".into(),
+    };
+    run(&mut writer, &scaled);
+    assert_eq!(writer.0, r#"Mode::Scaled[ factor=1.5 label="big" ]"#);
+    assert_eq!(scaled.variant_info().name, "Scaled");
+}
+
+#[test]
+fn test_runtime_selected_visitors() {
+    let value = plugin(Mode::Scaled {
+        factor: 2.0,
+        label: "x".into(),
+    });
+    let mut visitors: Vec<Box<dyn DynVisitor>> = vec![
+        Box::new(KeyValueWriter::default()),
+        Box::new(LeafCounter::default()),
+    ];
+    for visitor in &mut visitors {
+        value.dyn_visit_fields(visitor.as_mut());
+    }
+
+    let mut counter = LeafCounter::default();
+    value.dyn_visit(&mut counter);
+    assert_eq!(counter.0, 7);
+}
+
+#[test]
+fn test_non_dyn_fields_still_derive() {
+    let timed = Timed {
+        timeout: Duration::from_secs(1),
+    };
+    assert_eq!(timed.timeout.as_secs(), 1);
+}
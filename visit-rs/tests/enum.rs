@@ -1,10 +1,9 @@
 use std::fmt::Write;
-use std::future::Future;
 use visit_rs::{
-    EnumInfo, Named, Static, Variant, Visit, VisitAsync, VisitVariant, VisitVariantFields,
-    VisitVariantFieldsAsync, VisitVariantFieldsCovered, VisitVariantFieldsCoveredAsync,
-    VisitVariantFieldsNamed, VisitVariantFieldsNamedAsync, VisitVariantFieldsStatic,
-    VisitVariantFieldsStaticAsync, VisitVariantFieldsStaticNamed,
+    EnumInfo, Named, Static, StructInfoData, Variant, Visit, VisitAsync, VisitVariant,
+    VisitVariantFields, VisitVariantFieldsAsync, VisitVariantFieldsCovered,
+    VisitVariantFieldsCoveredAsync, VisitVariantFieldsNamed, VisitVariantFieldsNamedAsync,
+    VisitVariantFieldsStatic, VisitVariantFieldsStaticAsync, VisitVariantFieldsStaticNamed,
     VisitVariantFieldsStaticNamedAsync, VisitVariants, VisitVariantsStatic, Visitor,
 };
 
@@ -38,7 +37,7 @@ enum TestEnum {
     Unit,
     Single(String),
     Tuple(i32, f32),
-    Struct { foo: String, bar: Vec<u8> },
+    Struct { label: String, bar: Vec<u8> },
 }
 
 impl Visit<FmtVisitor> for String {
@@ -71,38 +70,38 @@ impl Visit<FmtVisitor> for Vec<u8> {
 }
 
 impl VisitAsync<FmtVisitor> for String {
-    fn visit_async<'a>(
+    async fn visit_async<'a>(
         &'a self,
         visitor: &'a mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'a {
-        async move { self.visit(visitor) }
+    ) -> <FmtVisitor as Visitor>::Result {
+        self.visit(visitor)
     }
 }
 
 impl VisitAsync<FmtVisitor> for i32 {
-    fn visit_async<'a>(
+    async fn visit_async<'a>(
         &'a self,
         visitor: &'a mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'a {
-        async move { self.visit(visitor) }
+    ) -> <FmtVisitor as Visitor>::Result {
+        self.visit(visitor)
     }
 }
 
 impl VisitAsync<FmtVisitor> for f32 {
-    fn visit_async<'a>(
+    async fn visit_async<'a>(
         &'a self,
         visitor: &'a mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'a {
-        async move { self.visit(visitor) }
+    ) -> <FmtVisitor as Visitor>::Result {
+        self.visit(visitor)
     }
 }
 
 impl VisitAsync<FmtVisitor> for Vec<u8> {
-    fn visit_async<'a>(
+    async fn visit_async<'a>(
         &'a self,
         visitor: &'a mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'a {
-        async move { self.visit(visitor) }
+    ) -> <FmtVisitor as Visitor>::Result {
+        self.visit(visitor)
     }
 }
 
@@ -159,18 +158,16 @@ impl<'a, T> VisitAsync<FmtVisitor> for Named<'a, T>
 where
     T: VisitAsync<FmtVisitor> + Sync,
 {
-    fn visit_async<'b>(
+    async fn visit_async<'b>(
         &'b self,
         visitor: &'b mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'b {
-        async move {
-            if let Some(name) = self.name {
-                write!(&mut visitor.0, "{name}:")?;
-            }
-            VisitAsync::visit_async(self.value, visitor).await?;
-            visitor.0.push(',');
-            Ok(())
+    ) -> <FmtVisitor as Visitor>::Result {
+        if let Some(name) = self.name {
+            write!(&mut visitor.0, "{name}:")?;
         }
+        VisitAsync::visit_async(self.value, visitor).await?;
+        visitor.0.push(',');
+        Ok(())
     }
 }
 
@@ -187,11 +184,11 @@ impl<'a, T> VisitAsync<FmtVisitor> for visit_rs::Covered<'a, T>
 where
     T: VisitAsync<FmtVisitor> + Sync + ?Sized,
 {
-    fn visit_async<'b>(
+    async fn visit_async<'b>(
         &'b self,
         visitor: &'b mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'b {
-        async move { VisitAsync::visit_async(self.0, visitor).await }
+    ) -> <FmtVisitor as Visitor>::Result {
+        VisitAsync::visit_async(self.0, visitor).await
     }
 }
 
@@ -199,11 +196,11 @@ impl<T> VisitAsync<FmtVisitor> for Static<T>
 where
     T: VisitAsync<FmtVisitor>,
 {
-    fn visit_async<'a>(
+    async fn visit_async<'a>(
         &'a self,
         _visitor: &'a mut FmtVisitor,
-    ) -> impl Future<Output = <FmtVisitor as Visitor>::Result> + Send + 'a {
-        async move { Ok(()) }
+    ) -> <FmtVisitor as Visitor>::Result {
+        Ok(())
     }
 }
 
@@ -233,10 +230,10 @@ fn test_test_enum() {
     assert_eq!(&fmt(&TestEnum::Tuple(1, 2.1)), "Tuple(1,2.1)");
     assert_eq!(
         &fmt(&TestEnum::Struct {
-            foo: "hello".into(),
+            label: "hello".into(),
             bar: vec![1, 2, 3]
         }),
-        "Struct{foo:\"hello\",bar:[1,2,3]}"
+        "Struct{label:\"hello\",bar:[1,2,3]}"
     )
 }
 
@@ -328,6 +325,139 @@ fn test_enum_info() {
     assert_eq!(variants[3].field_count, 2);
 }
 
+const CLOSE: u16 = 9;
+
+#[derive(VisitVariants)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Opcode {
+    Nop,
+    Load = 0x10,
+    Store,
+    Jump = 0x20 + 1,
+}
+
+#[derive(VisitVariants)]
+#[repr(C, u16)]
+#[allow(dead_code)]
+enum Frame {
+    Ping = 1,
+    Data(Vec<u8>) = 5,
+    Pong,
+    Close(u8) = CLOSE,
+    Reset,
+}
+
+#[derive(VisitVariants)]
+#[repr(u128)]
+#[allow(dead_code)]
+enum Huge {
+    Small = 1,
+    Large = u128::MAX,
+}
+
+#[derive(VisitVariants)]
+#[repr(u128)]
+#[allow(dead_code)]
+enum Big {
+    A(u8),
+    B,
+    Wide(u8) = 170141183460469231731687303715884105728,
+    Wider,
+}
+
+#[test]
+fn test_variant_discriminants() {
+    assert_eq!(Opcode::DATA.repr, Some("u8"));
+    assert_eq!(Frame::DATA.repr, Some("C, u16"));
+    assert_eq!(TestEnum::DATA.repr, None);
+
+    let opcodes: Vec<_> = Opcode::variants()
+        .into_iter()
+        .map(|variant| variant.variant.unwrap())
+        .collect();
+    let indexes: Vec<_> = opcodes.iter().map(|variant| variant.index).collect();
+    assert_eq!(indexes, vec![0, 1, 2, 3]);
+    let discriminants: Vec<_> = opcodes.iter().map(|variant| variant.discriminant).collect();
+    assert_eq!(
+        discriminants,
+        vec![None, Some("0x10"), None, Some("0x20 + 1")]
+    );
+    let values: Vec<_> = opcodes
+        .iter()
+        .map(|variant| variant.discriminant_value)
+        .collect();
+    assert_eq!(values, vec![Some(0), Some(0x10), Some(0x11), Some(0x21)]);
+
+    let values: Vec<_> = Frame::variants()
+        .into_iter()
+        .map(|variant| variant.variant.unwrap().discriminant_value)
+        .collect();
+    assert_eq!(values, vec![Some(1), Some(5), Some(6), None, None]);
+
+    // Above `i128::MAX`, rather than wrapped
+    let values: Vec<_> = Huge::variants()
+        .into_iter()
+        .map(|variant| variant.variant.unwrap().discriminant_value)
+        .collect();
+    assert_eq!(values, vec![Some(1), None]);
+    let values: Vec<_> = Big::variants()
+        .into_iter()
+        .map(|variant| variant.variant.unwrap().discriminant_value)
+        .collect();
+    assert_eq!(values, vec![Some(0), Some(1), None, None]);
+
+    assert_eq!(Opcode::Store.variant_index(), 2);
+    assert_eq!(Frame::Close(0).variant_index(), 3);
+    assert_eq!(Frame::Close(0).variant_info().variant.unwrap().index, 3);
+}
+
+/// An `EnumInfo` written by hand, without `variant_index` or `variant`
+enum Switch {
+    Off,
+    On,
+}
+
+impl EnumInfo for Switch {
+    const DATA: visit_rs::EnumInfoData = visit_rs::EnumInfoData {
+        name: "Switch",
+        variant_count: 2,
+        repr: None,
+        #[cfg(feature = "meta")]
+        metadata: &[],
+    };
+    fn variants() -> impl IntoIterator<Item = StructInfoData> + Send + Sync + 'static {
+        [switch_info("Off"), switch_info("On")]
+    }
+    fn variant_info(&self) -> StructInfoData {
+        match self {
+            Self::Off => switch_info("Off"),
+            Self::On => switch_info("On"),
+        }
+    }
+    fn variant_info_by_name(name: &str) -> Option<StructInfoData> {
+        Self::variants().into_iter().find(|info| info.name == name)
+    }
+}
+
+fn switch_info(name: &'static str) -> StructInfoData {
+    StructInfoData {
+        name,
+        named_fields: false,
+        field_count: 0,
+        fields: &[],
+        variant: None,
+        #[cfg(feature = "meta")]
+        metadata: &[],
+    }
+}
+
+#[test]
+fn test_default_variant_index() {
+    assert_eq!(Switch::Off.variant_index(), 0);
+    assert_eq!(Switch::On.variant_index(), 1);
+}
+
 #[test]
 fn test_visit_variant() {
     let mut visitor = TestVisitor;
@@ -366,14 +496,14 @@ fn test_visit_variant_fields() {
     assert_eq!(fields.len(), 1);
     assert_eq!(fields[0], "String(\"hello\")");
 
-    let tuple = TestEnum::Tuple(42, 3.14);
+    let tuple = TestEnum::Tuple(42, 2.5);
     let fields: Vec<_> = tuple.visit_variant_fields(&mut visitor).collect();
     assert_eq!(fields.len(), 2);
     assert_eq!(fields[0], "i32(42)");
-    assert_eq!(fields[1], "f32(3.14)");
+    assert_eq!(fields[1], "f32(2.5)");
 
     let struct_var = TestEnum::Struct {
-        foo: "bar".to_string(),
+        label: "bar".to_string(),
         bar: vec![1, 2, 3],
     };
     let fields: Vec<_> = struct_var.visit_variant_fields(&mut visitor).collect();
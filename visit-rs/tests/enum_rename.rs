@@ -24,7 +24,7 @@ enum MixedRenameEnum {
     FirstVariant,
     #[visit(rename = "CustomName")]
     SecondVariant,
-    ThirdVariant,
+    ThirdOption,
 }
 
 // Test field rename in enum variants
@@ -60,7 +60,14 @@ fn test_rename_all_screaming_snake_case() {
     let variants: Vec<_> = MixedRenameEnum::variants().into_iter().collect();
     assert_eq!(variants[0].name, "FIRST_VARIANT");
     assert_eq!(variants[1].name, "CustomName"); // override
-    assert_eq!(variants[2].name, "THIRD_VARIANT");
+    assert_eq!(variants[2].name, "THIRD_OPTION");
+}
+
+#[test]
+fn test_variant_field_rename() {
+    let variant = EnumWithFields::variants().into_iter().next().unwrap();
+    let names: Vec<_> = variant.fields.iter().map(|field| field.name).collect();
+    assert_eq!(names, vec![Some("renamed_field"), Some("other_field")]);
 }
 
 #[test]
@@ -106,3 +113,117 @@ fn test_case_conversions() {
     assert_eq!(KebabCase::variants().into_iter().next().unwrap().name, "test-variant");
     assert_eq!(ScreamingKebabCase::variants().into_iter().next().unwrap().name, "TEST-VARIANT");
 }
+
+// Test serde's split renames, aliases and field renaming in variants
+#[derive(VisitVariants)]
+#[visit(rename_all = "snake_case", rename_all_fields = "camelCase")]
+enum Command {
+    #[visit(alias = "begin", alias = "go")]
+    StartJob {
+        job_id: u32,
+        dry_run: bool,
+    },
+    #[visit(rename(serialize = "halt", deserialize = "stop"))]
+    #[visit(rename_all = "SCREAMING_SNAKE_CASE")]
+    StopJob {
+        job_id: u32,
+    },
+    Ping(u8),
+}
+
+#[derive(VisitVariants)]
+#[visit(rename_all(serialize = "kebab-case", deserialize = "PascalCase"))]
+enum Level {
+    VeryLow,
+    #[visit(rename(deserialize = "Hi"))]
+    High,
+}
+
+#[test]
+fn test_serde_variant_names() {
+    let variants: Vec<_> = Command::variants().into_iter().collect();
+    let names: Vec<_> = variants.iter().map(|info| info.name).collect();
+    assert_eq!(names, vec!["start_job", "halt", "ping"]);
+
+    let field_names = |info: &StructInfoData| -> Vec<_> {
+        info.fields
+            .iter()
+            .map(|field| field.name.unwrap())
+            .collect()
+    };
+    assert_eq!(field_names(&variants[0]), vec!["jobId", "dryRun"]);
+    assert_eq!(field_names(&variants[1]), vec!["JOB_ID"]);
+
+    let level: Vec<_> = Level::variants()
+        .into_iter()
+        .map(|info| info.name)
+        .collect();
+    assert_eq!(level, vec!["very-low", "high"]);
+}
+
+#[test]
+fn test_variant_info_by_alias() {
+    let name = |name| Command::variant_info_by_name(name).map(|info| info.name);
+    assert_eq!(name("start_job"), Some("start_job"));
+    assert_eq!(name("begin"), Some("start_job"));
+    assert_eq!(name("go"), Some("start_job"));
+    assert_eq!(name("halt"), Some("halt"));
+    assert_eq!(name("stop"), Some("halt"));
+    assert_eq!(name("stop_job"), None);
+
+    let name = |name| Level::variant_info_by_name(name).map(|info| info.name);
+    assert_eq!(name("VeryLow"), Some("very-low"));
+    assert_eq!(name("very-low"), Some("very-low"));
+    assert_eq!(name("Hi"), Some("high"));
+    assert_eq!(name("High"), None);
+}
+
+#[derive(VisitVariants, Debug, Clone, Copy, PartialEq)]
+#[visit(rename_all = "lowercase", unit_variants, from_str, display)]
+enum Color {
+    Red,
+    #[visit(alias = "grey")]
+    Gray,
+    #[visit(rename = "dark-blue")]
+    DarkBlue,
+}
+
+/// Only parses by name, and keeps its own `Display` and an inherent item that
+/// would collide with the helpers
+#[derive(VisitVariants, Debug, PartialEq)]
+#[visit(from_str)]
+enum Toggle {
+    On,
+    Off,
+}
+
+impl Toggle {
+    const ALL: &'static str = "on|off";
+}
+
+impl std::fmt::Display for Toggle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(if *self == Toggle::On { "[x]" } else { "[ ]" })
+    }
+}
+
+#[test]
+fn test_unit_variants() {
+    assert_eq!(Color::ALL, &[Color::Red, Color::Gray, Color::DarkBlue]);
+    assert_eq!(Color::all_values().collect::<Vec<_>>(), Color::ALL);
+
+    assert_eq!(Color::variant_by_name("red"), Some(Color::Red));
+    assert_eq!(Color::variant_by_name("grey"), Some(Color::Gray));
+    assert_eq!(Color::variant_by_name("dark-blue"), Some(Color::DarkBlue));
+    assert_eq!(Color::variant_by_name("Red"), None);
+    assert_eq!(Color::DarkBlue.as_variant_name(), "dark-blue");
+
+    assert_eq!("gray".parse(), Ok(Color::Gray));
+    assert_eq!(Color::Gray.to_string(), "gray");
+    let err = "blue".parse::<Color>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown variant `blue` of Color");
+
+    assert_eq!("Off".parse(), Ok(Toggle::Off));
+    assert_eq!(Toggle::On.to_string(), "[x]");
+    assert_eq!(Toggle::ALL, "on|off");
+}
@@ -0,0 +1,132 @@
+use visit_rs::{EnumInfo, FieldInfo, StructInfo, VisitFields, VisitVariants};
+
+#[derive(VisitFields)]
+#[visit(rename_all = "camelCase")]
+struct Account {
+    user_id: u64,
+    #[visit(rename = "mail")]
+    email_address: Option<String>,
+    #[visit(skip)]
+    _session: Vec<u8>,
+    display_name: &'static str,
+}
+
+#[derive(VisitFields)]
+#[visit(rename = "UserProfile", rename_all = "kebab-case")]
+struct Profile {
+    #[visit(rename = "handle")]
+    user_name: String,
+    #[visit(skip, rename = "cache")]
+    _cache: Vec<u8>,
+    avatar_url: String,
+}
+
+#[derive(VisitFields)]
+struct Wrapper<T>(T, [u8; 4]);
+
+#[derive(VisitVariants)]
+#[visit(rename_all = "snake_case")]
+enum Event {
+    Started,
+    Moved(i32, i32),
+    Renamed { old_name: String, new_name: String },
+}
+
+const ACCOUNT_FIELDS: usize = Account::FIELDS.len();
+const FIRST_NAME: Option<&str> = Account::FIELDS[0].name;
+
+fn names(fields: &[FieldInfo]) -> Vec<Option<&'static str>> {
+    fields.iter().map(|field| field.name).collect()
+}
+
+#[test]
+fn test_struct_fields() {
+    assert_eq!(ACCOUNT_FIELDS, 4);
+    assert_eq!(FIRST_NAME, Some("userId"));
+    assert_eq!(Account::DATA.field_count, 3);
+    assert_eq!(Account::DATA.fields, Account::FIELDS);
+
+    let fields = Account::FIELDS;
+    assert_eq!(
+        names(fields),
+        vec![
+            Some("userId"),
+            Some("mail"),
+            Some("session"),
+            Some("displayName")
+        ]
+    );
+    assert_eq!(fields[1].ident, Some("email_address"));
+    assert_eq!(fields[2].index, 2);
+    assert!(fields[2].skip);
+    assert!(!fields[3].skip);
+    assert_eq!(fields[1].type_name, "Option<String>");
+    assert_eq!(fields[3].type_name, "&'static str");
+}
+
+#[test]
+fn test_multi_item_attributes() {
+    assert_eq!(Profile::DATA.name, "UserProfile");
+    assert_eq!(Profile::DATA.field_count, 2);
+    assert_eq!(
+        names(Profile::FIELDS),
+        vec![Some("handle"), Some("cache"), Some("avatar-url")]
+    );
+    assert!(Profile::FIELDS[1].skip);
+}
+
+#[test]
+fn test_tuple_struct_fields() {
+    let fields = Wrapper::<bool>::FIELDS;
+    assert_eq!(names(fields), vec![None, None]);
+    assert_eq!(fields[0].ident, None);
+    assert_eq!(fields[0].type_name, "T");
+    assert_eq!(fields[1].type_name, "[u8; 4]");
+    assert_eq!(fields[1].index, 1);
+}
+
+#[test]
+fn test_variant_fields() {
+    let variants: Vec<_> = Event::variants().into_iter().collect();
+    assert!(variants[0].fields.is_empty());
+    assert_eq!(variants[1].fields.len(), 2);
+    assert_eq!(variants[1].fields[1].type_name, "i32");
+    assert_eq!(
+        names(variants[2].fields),
+        vec![Some("old_name"), Some("new_name")]
+    );
+
+    let event = Event::Renamed {
+        old_name: "a".to_string(),
+        new_name: "b".to_string(),
+    };
+    assert_eq!(event.variant_info().fields, variants[2].fields);
+    assert_eq!(
+        Event::variant_info_by_name("moved").map(|info| info.fields.len()),
+        Some(2)
+    );
+}
+
+#[cfg(feature = "meta")]
+#[test]
+fn test_field_metadata() {
+    use visit_rs::metadata::AttributeMeta;
+
+    assert_eq!(Account::FIELDS[0].metadata.len(), 0);
+    assert_eq!(Account::FIELDS[1].metadata.len(), 1);
+    assert_eq!(Account::FIELDS[2].metadata.len(), 1);
+
+    assert_eq!(Profile::DATA.metadata.len(), 1);
+    match Profile::FIELDS[1].metadata {
+        [AttributeMeta::List { path, items }] => {
+            assert_eq!(*path, "visit");
+            assert!(matches!(items[0], AttributeMeta::Path { path: "skip" }));
+            assert!(matches!(
+                items[1],
+                AttributeMeta::NameValue { name: "rename", .. }
+            ));
+            assert_eq!(items.len(), 2);
+        }
+        other => panic!("Expected one List attribute, got {other:?}"),
+    }
+}
@@ -0,0 +1,227 @@
+#![allow(clippy::manual_async_fn)]
+
+use std::future::Future;
+
+use visit_rs::{
+    Named, Static, Visit, VisitAsync, VisitField, VisitFieldAsync, VisitFieldStatic,
+    VisitFieldStaticAsync, VisitFields, Visitor,
+};
+
+/// Resolves a JSON-Pointer-style path like `/server/port` to a rendered value
+struct Lookup {
+    segments: Vec<String>,
+    visited: usize,
+}
+
+impl Visitor for Lookup {
+    type Result = Option<String>;
+}
+
+impl<'a> Visit<Lookup> for Named<'a, u16> {
+    fn visit(&self, visitor: &mut Lookup) -> Option<String> {
+        visitor.visited += 1;
+        visitor.segments.is_empty().then(|| self.value.to_string())
+    }
+}
+
+impl<'a> Visit<Lookup> for Named<'a, String> {
+    fn visit(&self, visitor: &mut Lookup) -> Option<String> {
+        visitor.visited += 1;
+        visitor.segments.is_empty().then(|| self.value.clone())
+    }
+}
+
+impl<'a> Visit<Lookup> for Named<'a, Server> {
+    fn visit(&self, visitor: &mut Lookup) -> Option<String> {
+        visitor.visited += 1;
+        let segment = visitor.segments.pop()?;
+        self.value.visit_field_by_name(&segment, visitor).flatten()
+    }
+}
+
+fn lookup(config: &Config, pointer: &str) -> (Option<String>, usize) {
+    let mut segments: Vec<_> = pointer.split('/').skip(1).map(String::from).collect();
+    segments.reverse();
+    let first = segments.pop().unwrap();
+    let mut visitor = Lookup {
+        segments,
+        visited: 0,
+    };
+    let found = config.visit_field_by_name(&first, &mut visitor).flatten();
+    (found, visitor.visited)
+}
+
+#[derive(VisitFields)]
+#[visit(rename_all = "kebab-case")]
+struct Config {
+    app_name: String,
+    #[visit(skip)]
+    _cache: Vec<u8>,
+    server: Server,
+}
+
+#[derive(VisitFields)]
+struct Server {
+    host: String,
+    #[visit(rename = "listen")]
+    port: u16,
+}
+
+#[derive(VisitFields)]
+#[visit(rename_all(serialize = "camelCase", deserialize = "kebab-case"))]
+struct Upstream {
+    #[visit(alias = "hostname", alias = "addr")]
+    host_name: String,
+    #[visit(rename(serialize = "portNumber", deserialize = "port"))]
+    port: u16,
+}
+
+#[derive(VisitFields)]
+struct Pair(u16, String);
+
+fn config() -> Config {
+    Config {
+        app_name: "demo".to_string(),
+        _cache: Vec::new(),
+        server: Server {
+            host: "localhost".to_string(),
+            port: 8080,
+        },
+    }
+}
+
+#[test]
+fn test_visit_field_by_name() {
+    let config = config();
+    assert_eq!(lookup(&config, "/app-name"), (Some("demo".to_string()), 1));
+    assert_eq!(
+        lookup(&config, "/server/listen"),
+        (Some("8080".to_string()), 2)
+    );
+    assert_eq!(lookup(&config, "/server/port"), (None, 1));
+    assert_eq!(lookup(&config, "/app_name"), (None, 0));
+    assert_eq!(lookup(&config, "/_cache"), (None, 0));
+}
+
+#[test]
+fn test_visit_field_by_index() {
+    let config = config();
+    let mut visitor = Lookup {
+        segments: Vec::new(),
+        visited: 0,
+    };
+    assert_eq!(
+        config.visit_field_by_index(0, &mut visitor),
+        Some(Some("demo".to_string()))
+    );
+    // Skipped fields keep their declaration index but cannot be visited
+    assert_eq!(config.visit_field_by_index(1, &mut visitor), None);
+    assert_eq!(config.visit_field_by_index(3, &mut visitor), None);
+    assert_eq!(visitor.visited, 1);
+
+    let pair = Pair(1, "one".to_string());
+    assert_eq!(pair.visit_field_by_name("0", &mut visitor), None);
+    assert_eq!(
+        pair.visit_field_by_index(1, &mut visitor),
+        Some(Some("one".to_string()))
+    );
+}
+
+/// Reports the type of a field
+struct TypeOf;
+
+impl Visitor for TypeOf {
+    type Result = &'static str;
+}
+
+impl<'a, T> Visit<TypeOf> for Named<'a, Static<T>> {
+    fn visit(&self, _visitor: &mut TypeOf) -> &'static str {
+        std::any::type_name::<T>()
+    }
+}
+
+impl<'a, T> VisitAsync<TypeOf> for Named<'a, Static<T>> {
+    fn visit_async<'b>(
+        &'b self,
+        _visitor: &'b mut TypeOf,
+    ) -> impl Future<Output = &'static str> + Send + 'b {
+        async move { std::any::type_name::<T>() }
+    }
+}
+
+#[test]
+fn test_visit_field_static() {
+    assert_eq!(
+        Server::visit_field_by_name_static("listen", &mut TypeOf),
+        Some("u16")
+    );
+    assert_eq!(
+        Server::visit_field_by_name_static("port", &mut TypeOf),
+        None
+    );
+    assert_eq!(
+        Config::visit_field_by_index_static(2, &mut TypeOf),
+        Some(std::any::type_name::<Server>())
+    );
+}
+
+impl<'a, T: Sync> VisitAsync<Lookup> for Named<'a, T>
+where
+    Named<'a, T>: Visit<Lookup>,
+{
+    fn visit_async<'b>(
+        &'b self,
+        visitor: &'b mut Lookup,
+    ) -> impl Future<Output = Option<String>> + Send + 'b {
+        async move { self.visit(visitor) }
+    }
+}
+
+#[tokio::test]
+async fn test_visit_field_async() {
+    let server = config().server;
+    let mut visitor = Lookup {
+        segments: Vec::new(),
+        visited: 0,
+    };
+    assert_eq!(
+        server.visit_field_by_name_async("host", &mut visitor).await,
+        Some(Some("localhost".to_string()))
+    );
+    assert_eq!(
+        server.visit_field_by_index_async(1, &mut visitor).await,
+        Some(Some("8080".to_string()))
+    );
+    assert_eq!(visitor.visited, 2);
+
+    assert_eq!(
+        Server::visit_field_by_name_static_async("host", &mut TypeOf).await,
+        Some(std::any::type_name::<String>())
+    );
+    assert_eq!(
+        Pair::visit_field_by_index_static_async(2, &mut TypeOf).await,
+        None
+    );
+}
+
+#[test]
+fn test_visit_field_by_alias() {
+    let upstream = Upstream {
+        host_name: "10.0.0.1".to_string(),
+        port: 443,
+    };
+    let find = |name: &str| {
+        let mut visitor = Lookup {
+            segments: Vec::new(),
+            visited: 0,
+        };
+        upstream.visit_field_by_name(name, &mut visitor).flatten()
+    };
+    for name in ["hostName", "host-name", "hostname", "addr"] {
+        assert_eq!(find(name), Some("10.0.0.1".to_string()), "{name}");
+    }
+    assert_eq!(find("portNumber"), Some("443".to_string()));
+    assert_eq!(find("port"), Some("443".to_string()));
+    assert_eq!(find("port-number"), None);
+    assert_eq!(find("host_name"), None);
+}
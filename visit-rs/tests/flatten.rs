@@ -0,0 +1,265 @@
+use std::ops::ControlFlow;
+
+use futures::StreamExt;
+use visit_rs::{
+    ForkVisitor, Named, NamedMut, Static, StructInfo, Visit, VisitAsync, VisitField, VisitFields,
+    VisitFieldsConcurrent, VisitFieldsMut, VisitFieldsNamed, VisitFieldsNamedAsync,
+    VisitFieldsNamedMut, VisitFieldsOwned, VisitFieldsStatic, VisitFieldsStaticNamed, VisitMut,
+    VisitOwned, Visitor,
+};
+
+#[derive(VisitFields)]
+#[visit(owned)]
+struct Audit {
+    created_by: String,
+    updated_by: String,
+}
+
+#[derive(VisitFields)]
+#[visit(owned)]
+struct Document {
+    title: String,
+    #[visit(flatten)]
+    audit: Audit,
+    body: String,
+}
+
+/// Records what it visits, stopping at the string "stop"
+#[derive(Default)]
+struct Log(Vec<String>);
+
+impl Visitor for Log {
+    type Result = ControlFlow<()>;
+    fn is_break(result: &Self::Result) -> bool {
+        result.is_break()
+    }
+}
+
+impl ForkVisitor for Log {
+    fn fork(&self) -> Self {
+        Log::default()
+    }
+}
+
+impl Visit<Log> for String {
+    fn visit(&self, visitor: &mut Log) -> ControlFlow<()> {
+        visitor.0.push(self.clone());
+        if self == "stop" {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+impl VisitAsync<Log> for String {
+    async fn visit_async<'a>(&'a self, visitor: &'a mut Log) -> ControlFlow<()> {
+        self.visit(visitor)
+    }
+}
+
+impl VisitMut<Log> for String {
+    fn visit_mut(&mut self, visitor: &mut Log) -> ControlFlow<()> {
+        self.make_ascii_uppercase();
+        Visit::visit(self, visitor)
+    }
+}
+
+impl VisitOwned<Log> for String {
+    fn visit_owned(self, visitor: &mut Log) -> ControlFlow<()> {
+        Visit::visit(&self, visitor)
+    }
+}
+
+impl<'a, T: VisitMut<Log> + ?Sized> VisitMut<Log> for NamedMut<'a, T> {
+    fn visit_mut(&mut self, visitor: &mut Log) -> ControlFlow<()> {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_mut(visitor)
+    }
+}
+
+impl<T: ?Sized> Visit<Log> for Static<T> {
+    fn visit(&self, visitor: &mut Log) -> ControlFlow<()> {
+        let name = std::any::type_name::<T>();
+        visitor
+            .0
+            .push(name.rsplit("::").next().unwrap_or(name).to_string());
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, T: Visit<Log> + ?Sized> Visit<Log> for Named<'a, T> {
+    fn visit(&self, visitor: &mut Log) -> ControlFlow<()> {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit(visitor)
+    }
+}
+
+impl<'a, T: VisitAsync<Log> + Sync + ?Sized> VisitAsync<Log> for Named<'a, T> {
+    async fn visit_async<'b>(&'b self, visitor: &'b mut Log) -> ControlFlow<()> {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_async(visitor).await
+    }
+}
+
+fn document(updated_by: &str) -> Document {
+    Document {
+        title: "notes".to_string(),
+        audit: Audit {
+            created_by: "ada".to_string(),
+            updated_by: updated_by.to_string(),
+        },
+        body: "text".to_string(),
+    }
+}
+
+#[test]
+fn test_flatten() {
+    let mut log = Log::default();
+    let results: Vec<_> = document("grace").visit_fields(&mut log).collect();
+    assert_eq!(results.len(), 4);
+    assert_eq!(log.0, vec!["notes", "ada", "grace", "text"]);
+
+    let mut log = Log::default();
+    document("grace")
+        .visit_fields_named(&mut log)
+        .for_each(drop);
+    assert_eq!(
+        log.0,
+        vec![
+            "title:",
+            "notes",
+            "created_by:",
+            "ada",
+            "updated_by:",
+            "grace",
+            "body:",
+            "text"
+        ]
+    );
+
+    let mut log = Log::default();
+    let mut doc = document("grace");
+    doc.visit_fields_mut(&mut log).for_each(drop);
+    assert_eq!(doc.audit.created_by, "ADA");
+    assert_eq!(log.0, vec!["NOTES", "ADA", "GRACE", "TEXT"]);
+}
+
+/// Every sync visit hands the visitor on from a flattened field's fields to the
+/// next field; `cargo +nightly miri test --test flatten` checks the borrows
+#[test]
+fn test_flatten_sync_visits() {
+    let mut log = Log::default();
+    let doc = document("grace");
+    let mut results = doc.visit_fields(&mut log);
+    assert!(results.nth(1).is_some());
+    drop(results);
+    log.0.push("paused".to_string());
+    assert_eq!(doc.visit_fields(&mut log).count(), 4);
+    assert_eq!(
+        log.0,
+        vec!["notes", "ada", "paused", "notes", "ada", "grace", "text"]
+    );
+
+    let mut log = Log::default();
+    let mut doc = document("grace");
+    doc.visit_fields_named_mut(&mut log).for_each(drop);
+    assert_eq!(doc.audit.updated_by, "GRACE");
+    assert_eq!(
+        log.0,
+        vec![
+            "title:",
+            "NOTES",
+            "created_by:",
+            "ADA",
+            "updated_by:",
+            "GRACE",
+            "body:",
+            "TEXT"
+        ]
+    );
+
+    let mut log = Log::default();
+    document("grace")
+        .visit_fields_owned(&mut log)
+        .for_each(drop);
+    assert_eq!(log.0, vec!["notes", "ada", "grace", "text"]);
+
+    let mut log = Log::default();
+    Document::visit_fields_static(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["String", "String", "String", "String"]);
+}
+
+#[test]
+fn test_flatten_break() {
+    let mut log = Log::default();
+    let results: Vec<_> = document("stop").visit_fields(&mut log).collect();
+    assert_eq!(results.last(), Some(&ControlFlow::Break(())));
+    assert_eq!(log.0, vec!["notes", "ada", "stop"]);
+}
+
+#[test]
+fn test_flatten_info() {
+    assert_eq!(Document::DATA.field_count, 4);
+    assert!(Document::FIELDS[1].flatten);
+    assert!(!Document::FIELDS[0].flatten);
+
+    let mut log = Log::default();
+    Document::visit_fields_static_named(&mut log).for_each(drop);
+    assert_eq!(
+        log.0,
+        vec![
+            "title:",
+            "String",
+            "created_by:",
+            "String",
+            "updated_by:",
+            "String",
+            "body:",
+            "String"
+        ]
+    );
+}
+
+#[test]
+fn test_flatten_lookup() {
+    let doc = document("grace");
+
+    let mut log = Log::default();
+    assert!(doc.visit_field_by_name("updated_by", &mut log).is_some());
+    assert!(doc.visit_field_by_name("audit", &mut log).is_none());
+    assert!(doc.visit_field_by_index(1, &mut log).is_none());
+    assert!(doc.visit_field_by_index(2, &mut log).is_some());
+    assert_eq!(log.0, vec!["updated_by:", "grace", "body:", "text"]);
+}
+
+#[tokio::test]
+async fn test_flatten_async() {
+    let doc = document("grace");
+
+    let mut log = Log::default();
+    doc.visit_fields_named_async(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(
+        log.0,
+        vec![
+            "title:",
+            "notes",
+            "created_by:",
+            "ada",
+            "updated_by:",
+            "grace",
+            "body:",
+            "text"
+        ]
+    );
+
+    let mut indexes: Vec<_> = doc
+        .visit_fields_concurrent(&Log::default(), 2)
+        .map(|(index, _)| index)
+        .collect()
+        .await;
+    indexes.sort();
+    assert_eq!(indexes, vec![0, 1, 1, 2]);
+}
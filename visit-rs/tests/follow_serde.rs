@@ -0,0 +1,158 @@
+use std::ops::ControlFlow;
+
+use futures::StreamExt;
+use visit_rs::{
+    ForkVisitor, Named, NamedMut, StructInfo, Visit, VisitAsync, VisitFields, VisitFieldsNamed,
+    VisitFieldsNamedAsync, VisitFieldsNamedConcurrent, VisitFieldsNamedMut, VisitMut, Visitor,
+};
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+#[derive(VisitFields)]
+#[visit(follow_serde)]
+#[allow(dead_code)]
+struct Account {
+    name: String,
+    #[visit(skip_serializing)]
+    password: String,
+    #[visit(skip)]
+    session: String,
+    #[visit(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[visit(skip_serializing_if = "is_zero")]
+    logins: u32,
+}
+
+#[derive(VisitFields)]
+struct Unfollowed {
+    name: String,
+    #[visit(skip_serializing)]
+    password: String,
+}
+
+/// Records the names of the fields it visits
+#[derive(Default)]
+struct Names(Vec<&'static str>);
+
+impl Visitor for Names {
+    type Result = ControlFlow<()>;
+    fn is_break(result: &Self::Result) -> bool {
+        result.is_break()
+    }
+}
+
+impl ForkVisitor for Names {
+    fn fork(&self) -> Self {
+        Names::default()
+    }
+}
+
+macro_rules! visit_nothing {
+    ($($ty:ty),*) => {
+        $(impl Visit<Names> for $ty {
+            fn visit(&self, _: &mut Names) -> ControlFlow<()> {
+                ControlFlow::Continue(())
+            }
+        })*
+    };
+}
+
+visit_nothing!(String, Option<String>, u32);
+
+impl<'a, T: ?Sized> Visit<Names> for Named<'a, T> {
+    fn visit(&self, visitor: &mut Names) -> ControlFlow<()> {
+        visitor.0.push(self.name.unwrap_or("?"));
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, T: Sync + ?Sized> VisitAsync<Names> for Named<'a, T> {
+    async fn visit_async<'b>(&'b self, visitor: &'b mut Names) -> ControlFlow<()> {
+        self.visit(visitor)
+    }
+}
+
+impl<'a, T: ?Sized> VisitMut<Names> for NamedMut<'a, T> {
+    fn visit_mut(&mut self, visitor: &mut Names) -> ControlFlow<()> {
+        visitor.0.push(self.name.unwrap_or("?"));
+        ControlFlow::Continue(())
+    }
+}
+
+fn account(email: Option<&str>, logins: u32) -> Account {
+    Account {
+        name: "ada".to_string(),
+        password: "hunter2".to_string(),
+        session: "abc".to_string(),
+        email: email.map(String::from),
+        logins,
+    }
+}
+
+#[test]
+fn test_follow_serde_skip() {
+    assert_eq!(Account::DATA.field_count, 3);
+    let skipped: Vec<_> = Account::FIELDS.iter().map(|field| field.skip).collect();
+    assert_eq!(skipped, vec![false, true, true, false, false]);
+
+    // Predicates are left to the named visits
+    let mut names = Names::default();
+    assert_eq!(account(None, 0).visit_fields(&mut names).count(), 3);
+
+    // Without `follow_serde`, serde's skips are only metadata
+    assert_eq!(Unfollowed::DATA.field_count, 2);
+    let mut names = Names::default();
+    Unfollowed {
+        name: "ada".to_string(),
+        password: "hunter2".to_string(),
+    }
+    .visit_fields_named(&mut names)
+    .for_each(drop);
+    assert_eq!(names.0, vec!["name", "password"]);
+}
+
+#[test]
+fn test_follow_serde_skip_serializing_if() {
+    let mut names = Names::default();
+    account(None, 0)
+        .visit_fields_named(&mut names)
+        .for_each(drop);
+    assert_eq!(names.0, vec!["name"]);
+
+    let mut names = Names::default();
+    account(Some("ada@example.com"), 3)
+        .visit_fields_named(&mut names)
+        .for_each(drop);
+    assert_eq!(names.0, vec!["name", "email", "logins"]);
+
+    let mut names = Names::default();
+    account(None, 3)
+        .visit_fields_named_mut(&mut names)
+        .for_each(drop);
+    assert_eq!(names.0, vec!["name", "logins"]);
+}
+
+#[tokio::test]
+async fn test_follow_serde_async() {
+    let account = account(Some("ada@example.com"), 0);
+    let mut names = Names::default();
+    account
+        .visit_fields_named_async(&mut names)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(names.0, vec!["name", "email"]);
+}
+
+#[tokio::test]
+async fn test_follow_serde_concurrent() {
+    let account = account(Some("ada@example.com"), 0);
+    let mut indexes: Vec<_> = account
+        .visit_fields_named_concurrent(&Names::default(), 2)
+        .map(|(index, _)| index)
+        .collect()
+        .await;
+    indexes.sort();
+    assert_eq!(indexes, vec![0, 3]);
+}
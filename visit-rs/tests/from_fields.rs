@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use visit_rs::{
+    Build, EnumInfo, FromFields, FromVariantFields, Named, Source, Static, StructInfo,
+    StructInfoData, VisitFields, VisitVariants, Visitor,
+};
+
+struct MapSource {
+    values: HashMap<&'static str, &'static str>,
+    positional: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq)]
+enum SourceError {
+    Missing(&'static str),
+    Invalid(&'static str),
+}
+
+impl Visitor for MapSource {
+    type Result = ();
+}
+
+impl Source for MapSource {
+    type Error = SourceError;
+}
+
+impl<'a, T: FromStr> Build<MapSource> for Named<'a, Static<T>> {
+    type Output = T;
+    fn build(&self, source: &mut MapSource) -> Result<T, SourceError> {
+        let (key, raw) = match self.name {
+            Some(name) => (name, source.values.get(name).copied()),
+            None => ("<positional>", Some(source.positional.remove(0))),
+        };
+        raw.ok_or(SourceError::Missing(key))?
+            .parse()
+            .map_err(|_| SourceError::Invalid(key))
+    }
+}
+
+fn source(values: &[(&'static str, &'static str)]) -> MapSource {
+    MapSource {
+        values: values.iter().copied().collect(),
+        positional: Vec::new(),
+    }
+}
+
+#[derive(Debug, PartialEq, VisitFields)]
+#[visit(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ServerConfig {
+    host: String,
+    #[visit(rename = "LISTEN_PORT")]
+    port: u16,
+    #[visit(skip)]
+    connections: Vec<u32>,
+    verbose: bool,
+}
+
+#[derive(Debug, PartialEq, VisitFields)]
+struct Point(i32, i32);
+
+/// Not buildable, as `File` has no `Default`, but still derives the rest
+#[derive(VisitFields)]
+#[allow(dead_code)]
+struct LogFile {
+    path: String,
+    #[visit(skip)]
+    file: std::fs::File,
+}
+
+#[derive(Debug, PartialEq, VisitVariants)]
+#[visit(rename_all = "snake_case")]
+enum Backend {
+    InMemory,
+    Disk { path: String, max_size: u64 },
+    Remote(String),
+}
+
+#[test]
+fn test_from_fields() {
+    let mut src = source(&[
+        ("HOST", "localhost"),
+        ("LISTEN_PORT", "8080"),
+        ("VERBOSE", "true"),
+    ]);
+    assert_eq!(
+        ServerConfig::from_fields(&mut src),
+        Ok(ServerConfig {
+            host: "localhost".to_string(),
+            port: 8080,
+            connections: Vec::new(),
+            verbose: true,
+        })
+    );
+}
+
+#[test]
+fn test_from_fields_errors() {
+    let mut src = source(&[("HOST", "localhost"), ("LISTEN_PORT", "8080")]);
+    assert_eq!(
+        ServerConfig::from_fields(&mut src),
+        Err(SourceError::Missing("VERBOSE"))
+    );
+
+    let mut src = source(&[("HOST", "localhost"), ("LISTEN_PORT", "http")]);
+    assert_eq!(
+        ServerConfig::from_fields(&mut src),
+        Err(SourceError::Invalid("LISTEN_PORT"))
+    );
+}
+
+#[test]
+fn test_from_fields_tuple_struct() {
+    let mut src = source(&[]);
+    src.positional = vec!["3", "-4"];
+    assert_eq!(Point::from_fields(&mut src), Ok(Point(3, -4)));
+}
+
+#[test]
+fn test_from_variant_fields() {
+    let mut src = source(&[("path", "/var/db"), ("max_size", "1024")]);
+    assert_eq!(
+        Backend::from_variant_name("disk", &mut src),
+        Some(Ok(Backend::Disk {
+            path: "/var/db".to_string(),
+            max_size: 1024,
+        }))
+    );
+    assert_eq!(
+        Backend::from_variant_name("in_memory", &mut src),
+        Some(Ok(Backend::InMemory))
+    );
+
+    src.positional = vec!["https://example.com"];
+    assert_eq!(
+        Backend::from_variant_name("remote", &mut src),
+        Some(Ok(Backend::Remote("https://example.com".to_string())))
+    );
+
+    assert_eq!(Backend::from_variant_name("Disk", &mut src), None);
+}
+
+/// `LogFile::from_fields` failing to compile is checked in
+/// `ui/from_fields_skip_without_default.rs`
+#[test]
+fn test_from_fields_skip_without_default() {
+    assert_eq!(LogFile::DATA.field_count, 1);
+}
+
+#[test]
+fn test_from_variant_fields_unknown() {
+    let mut src = source(&[]);
+    assert_eq!(
+        Backend::from_variant_fields(&ServerConfig::DATA, &mut src),
+        None
+    );
+
+    // The index and the name must both match
+    let remote = Backend::variant_info_by_name("remote").unwrap();
+    let mismatched = StructInfoData {
+        name: "disk",
+        ..remote
+    };
+    assert_eq!(Backend::from_variant_fields(&mismatched, &mut src), None);
+}
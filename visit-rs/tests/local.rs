@@ -0,0 +1,205 @@
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+use futures::StreamExt;
+use visit_rs::{
+    Named, VisitAsyncLocal, VisitFields, VisitFieldsAsyncLocal, VisitFieldsNamedAsyncLocal,
+    VisitVariantFieldsAsyncLocal, VisitVariantFieldsNamedAsyncLocal, VisitVariants, Visitor,
+};
+
+/// Records visited values into a log shared with the caller, neither of which is
+/// `Send`
+#[derive(Clone, Default)]
+struct Recorder {
+    log: Rc<RefCell<Vec<String>>>,
+}
+
+impl Visitor for Recorder {
+    type Result = ControlFlow<Rc<str>>;
+    fn is_break(result: &Self::Result) -> bool {
+        result.is_break()
+    }
+}
+
+impl VisitAsyncLocal<Recorder> for Rc<str> {
+    async fn visit_async_local<'a>(&'a self, visitor: &'a mut Recorder) -> ControlFlow<Rc<str>> {
+        tokio::task::yield_now().await;
+        visitor.log.borrow_mut().push(self.to_string());
+        if self.is_empty() {
+            ControlFlow::Break(self.clone())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+impl<'a> VisitAsyncLocal<Recorder> for Named<'a, Rc<str>> {
+    async fn visit_async_local<'b>(&'b self, visitor: &'b mut Recorder) -> ControlFlow<Rc<str>> {
+        let res = self.value.visit_async_local(visitor).await;
+        if let Some(name) = self.name {
+            visitor.log.borrow_mut().push(format!("^ {name}"));
+        }
+        res
+    }
+}
+
+#[derive(VisitFields)]
+struct Labels {
+    first: Rc<str>,
+    second: Rc<str>,
+    third: Rc<str>,
+}
+
+#[derive(VisitVariants)]
+enum Tag {
+    Plain(Rc<str>),
+    Pair { key: Rc<str>, value: Rc<str> },
+    None,
+}
+
+/// Stands in for a foreign type with no impls of its own
+struct Celsius(i32);
+
+/// Visitors that can record a line
+trait Record: Visitor {
+    fn record(&mut self, line: String) -> Self::Result;
+}
+
+impl Record for Recorder {
+    fn record(&mut self, line: String) -> ControlFlow<Rc<str>> {
+        self.log.borrow_mut().push(line);
+        ControlFlow::Continue(())
+    }
+}
+
+/// Visits a `Celsius` without asking anything of the visitor, so that its local
+/// visits serve a `Recorder`
+mod celsius {
+    use super::{Celsius, Record};
+
+    pub fn visit<V: Record>(value: &Celsius, visitor: &mut V) -> V::Result {
+        visitor.record(format!("{}C", value.0))
+    }
+
+    pub async fn visit_async<V: Record>(value: &Celsius, visitor: &mut V) -> V::Result {
+        tokio::task::yield_now().await;
+        visit(value, visitor)
+    }
+
+    pub fn visit_static<V: Record>(visitor: &mut V) -> V::Result {
+        visitor.record("C".to_string())
+    }
+
+    pub async fn visit_static_async<V: Record>(visitor: &mut V) -> V::Result {
+        visit_static(visitor)
+    }
+
+    pub fn visit_mut<V: Record>(value: &mut Celsius, visitor: &mut V) -> V::Result {
+        visit(value, visitor)
+    }
+}
+
+#[derive(VisitFields)]
+struct Forecast {
+    #[visit(with = "celsius")]
+    #[visit(bound(
+        visit = "__visit_rs__V: Record",
+        visit_async = "__visit_rs__V: Record",
+        visit_mut = "__visit_rs__V: Record"
+    ))]
+    low: Celsius,
+    #[visit(with = "celsius")]
+    #[visit(bound(
+        visit = "__visit_rs__V: Record",
+        visit_async = "__visit_rs__V: Record",
+        visit_mut = "__visit_rs__V: Record"
+    ))]
+    high: Celsius,
+}
+
+fn labels(second: &str) -> Labels {
+    Labels {
+        first: "a".into(),
+        second: second.into(),
+        third: "c".into(),
+    }
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_visit_fields_async_local() {
+    let mut recorder = Recorder::default();
+    let results: Vec<_> = labels("b")
+        .visit_fields_async_local(&mut recorder)
+        .collect()
+        .await;
+    assert_eq!(results.len(), 3);
+    assert_eq!(*recorder.log.borrow(), vec!["a", "b", "c"]);
+
+    let mut recorder = Recorder::default();
+    assert_eq!(
+        labels("")
+            .try_visit_fields_named_async_local(&mut recorder)
+            .await,
+        ControlFlow::Break(ControlFlow::Break(Rc::from("")))
+    );
+    assert_eq!(*recorder.log.borrow(), vec!["a", "^ first", "", "^ second"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_visit_variant_fields_async_local() {
+    let pair = Tag::Pair {
+        key: "k".into(),
+        value: "v".into(),
+    };
+    let mut recorder = Recorder::default();
+    pair.visit_variant_fields_named_async_local(&mut recorder)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(*recorder.log.borrow(), vec!["k", "^ key", "v", "^ value"]);
+
+    let mut recorder = Recorder::default();
+    assert!(
+        Tag::Plain("p".into())
+            .try_visit_variant_fields_async_local(&mut recorder)
+            .await
+            .is_continue()
+    );
+    let none = Tag::None.visit_variant_fields_async_local(&mut recorder);
+    assert!(std::pin::pin!(none).next().await.is_none());
+    assert_eq!(*recorder.log.borrow(), vec!["p"]);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_visit_with_async_local() {
+    let forecast = Forecast {
+        low: Celsius(-3),
+        high: Celsius(8),
+    };
+    let mut recorder = Recorder::default();
+    forecast
+        .visit_fields_async_local(&mut recorder)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(*recorder.log.borrow(), vec!["-3C", "8C"]);
+}
+
+#[test]
+fn test_local_set() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let local = tokio::task::LocalSet::new();
+    let recorder = Recorder::default();
+    let log = recorder.log.clone();
+    local.block_on(&runtime, async move {
+        tokio::task::spawn_local(async move {
+            let mut recorder = recorder;
+            let labels = labels("b");
+            let _ = labels.try_visit_fields_async_local(&mut recorder).await;
+        })
+        .await
+        .unwrap();
+    });
+    assert_eq!(*log.borrow(), vec!["a", "b", "c"]);
+}
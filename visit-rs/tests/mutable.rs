@@ -0,0 +1,125 @@
+use visit_rs::{
+    NamedMut, VisitFields, VisitFieldsMut, VisitFieldsNamedMut, VisitMut, VisitVariantFieldsMut,
+    VisitVariantFieldsNamedMut, VisitVariants, Visitor,
+};
+
+struct TrimVisitor;
+
+impl Visitor for TrimVisitor {
+    type Result = ();
+}
+
+impl VisitMut<TrimVisitor> for String {
+    fn visit_mut(&mut self, _visitor: &mut TrimVisitor) {
+        *self = self.trim().to_string();
+    }
+}
+
+impl VisitMut<TrimVisitor> for u32 {
+    fn visit_mut(&mut self, _visitor: &mut TrimVisitor) {}
+}
+
+struct RedactVisitor {
+    redacted: Vec<&'static str>,
+}
+
+impl Visitor for RedactVisitor {
+    type Result = ();
+}
+
+impl<'a> VisitMut<RedactVisitor> for NamedMut<'a, String> {
+    fn visit_mut(&mut self, visitor: &mut RedactVisitor) {
+        if let Some(name) = self.name
+            && (name.contains("secret") || name.contains("password"))
+        {
+            *self.value = "<redacted>".to_string();
+            visitor.redacted.push(name);
+        }
+    }
+}
+
+impl<'a> VisitMut<RedactVisitor> for NamedMut<'a, u32> {
+    fn visit_mut(&mut self, _visitor: &mut RedactVisitor) {}
+}
+
+#[derive(VisitFields)]
+struct Credentials {
+    user: String,
+    #[visit(rename = "password")]
+    pass: String,
+    port: u32,
+}
+
+#[derive(VisitFields)]
+struct Pair(String, String);
+
+#[derive(VisitVariants)]
+enum Login {
+    Anonymous,
+    Token(String),
+    Basic { user: String, api_secret: String },
+}
+
+#[test]
+fn test_visit_fields_mut() {
+    let mut creds = Credentials {
+        user: "  admin ".to_string(),
+        pass: " hunter2".to_string(),
+        port: 22,
+    };
+    let _: Vec<()> = creds.visit_fields_mut(&mut TrimVisitor).collect();
+    assert_eq!(creds.user, "admin");
+    assert_eq!(creds.pass, "hunter2");
+    assert_eq!(creds.port, 22);
+
+    let mut pair = Pair(" a ".to_string(), "b ".to_string());
+    let _: Vec<()> = pair.visit_fields_mut(&mut TrimVisitor).collect();
+    assert_eq!(pair.0, "a");
+    assert_eq!(pair.1, "b");
+}
+
+#[test]
+fn test_visit_fields_named_mut() {
+    let mut creds = Credentials {
+        user: "admin".to_string(),
+        pass: "hunter2".to_string(),
+        port: 22,
+    };
+    let mut visitor = RedactVisitor {
+        redacted: Vec::new(),
+    };
+    let _: Vec<()> = creds.visit_fields_named_mut(&mut visitor).collect();
+    assert_eq!(creds.user, "admin");
+    assert_eq!(creds.pass, "<redacted>");
+    assert_eq!(visitor.redacted, vec!["password"]);
+}
+
+#[test]
+fn test_visit_variant_fields_mut() {
+    let mut login = Login::Token(" abc ".to_string());
+    let _: Vec<()> = login.visit_variant_fields_mut(&mut TrimVisitor).collect();
+    assert!(matches!(&login, Login::Token(t) if t == "abc"));
+
+    let mut login = Login::Anonymous;
+    assert_eq!(login.visit_variant_fields_mut(&mut TrimVisitor).count(), 0);
+}
+
+#[test]
+fn test_visit_variant_fields_named_mut() {
+    let mut login = Login::Basic {
+        user: "admin".to_string(),
+        api_secret: "s3cr3t".to_string(),
+    };
+    let mut visitor = RedactVisitor {
+        redacted: Vec::new(),
+    };
+    let _: Vec<()> = login.visit_variant_fields_named_mut(&mut visitor).collect();
+    match login {
+        Login::Basic { user, api_secret } => {
+            assert_eq!(user, "admin");
+            assert_eq!(api_secret, "<redacted>");
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(visitor.redacted, vec!["api_secret"]);
+}
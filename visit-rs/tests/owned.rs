@@ -0,0 +1,166 @@
+use visit_rs::{
+    EnumInfo, StructInfo, VisitFields, VisitFieldsOwned, VisitOwned, VisitVariantFieldsOwned,
+    VisitVariants, Visitor,
+};
+
+#[derive(Debug, PartialEq)]
+enum Cell {
+    Text(String),
+    Int(i64),
+    Bytes(Vec<u8>),
+}
+
+struct RowBuilder {
+    cells: Vec<Cell>,
+}
+
+impl Visitor for RowBuilder {
+    type Result = ();
+}
+
+impl VisitOwned<RowBuilder> for String {
+    fn visit_owned(self, visitor: &mut RowBuilder) {
+        visitor.cells.push(Cell::Text(self));
+    }
+}
+
+impl VisitOwned<RowBuilder> for i64 {
+    fn visit_owned(self, visitor: &mut RowBuilder) {
+        visitor.cells.push(Cell::Int(self));
+    }
+}
+
+impl VisitOwned<RowBuilder> for Vec<u8> {
+    fn visit_owned(self, visitor: &mut RowBuilder) {
+        visitor.cells.push(Cell::Bytes(self));
+    }
+}
+
+fn build_row<T: VisitFieldsOwned<RowBuilder>>(value: T) -> Vec<Cell> {
+    let mut builder = RowBuilder { cells: Vec::new() };
+    value.visit_fields_owned(&mut builder).for_each(drop);
+    builder.cells
+}
+
+#[derive(VisitFields)]
+#[visit(owned)]
+struct Record {
+    name: String,
+    #[visit(skip)]
+    _cache: Option<usize>,
+    id: i64,
+    payload: Vec<u8>,
+}
+
+#[derive(VisitFields)]
+#[visit(owned)]
+struct Pair<T>(T, i64);
+
+// Without `#[visit(owned)]`, a type that implements `Drop` derives the rest
+#[derive(VisitFields)]
+struct Guarded {
+    name: String,
+}
+
+impl Drop for Guarded {
+    fn drop(&mut self) {}
+}
+
+#[derive(VisitVariants)]
+enum GuardedMessage {
+    Text(String),
+}
+
+impl Drop for GuardedMessage {
+    fn drop(&mut self) {}
+}
+
+#[derive(VisitVariants)]
+#[visit(owned)]
+enum Message {
+    Ping,
+    Text(String),
+    Blob { id: i64, data: Vec<u8> },
+}
+
+#[test]
+fn test_visit_fields_owned() {
+    let record = Record {
+        name: "row".to_string(),
+        _cache: Some(1),
+        id: 7,
+        payload: vec![1, 2],
+    };
+    assert_eq!(
+        build_row(record),
+        vec![
+            Cell::Text("row".to_string()),
+            Cell::Int(7),
+            Cell::Bytes(vec![1, 2]),
+        ]
+    );
+}
+
+#[test]
+fn test_visit_fields_owned_tuple_struct() {
+    assert_eq!(
+        build_row(Pair(vec![0u8], 3)),
+        vec![Cell::Bytes(vec![0]), Cell::Int(3)]
+    );
+}
+
+#[test]
+fn test_visit_fields_owned_is_lazy() {
+    let record = Record {
+        name: "row".to_string(),
+        _cache: None,
+        id: 7,
+        payload: vec![],
+    };
+    let mut builder = RowBuilder { cells: Vec::new() };
+    let mut iter = record.visit_fields_owned(&mut builder);
+    iter.next();
+    drop(iter);
+    assert_eq!(builder.cells, vec![Cell::Text("row".to_string())]);
+}
+
+#[test]
+fn test_drop_type_without_owned() {
+    assert_eq!(Guarded::DATA.field_count, 1);
+    let guarded = Guarded {
+        name: "guarded".to_string(),
+    };
+    assert_eq!(guarded.name, "guarded");
+
+    let message = GuardedMessage::Text("guarded".to_string());
+    assert_eq!(message.variant_info().name, "Text");
+}
+
+#[test]
+fn test_visit_variant_fields_owned() {
+    let mut builder = RowBuilder { cells: Vec::new() };
+    assert_eq!(
+        Message::Ping
+            .visit_variant_fields_owned(&mut builder)
+            .count(),
+        0
+    );
+
+    Message::Text("hi".to_string())
+        .visit_variant_fields_owned(&mut builder)
+        .for_each(drop);
+    Message::Blob {
+        id: 9,
+        data: vec![3],
+    }
+    .visit_variant_fields_owned(&mut builder)
+    .for_each(drop);
+    assert_eq!(
+        builder.cells,
+        vec![
+            Cell::Text("hi".to_string()),
+            Cell::Int(9),
+            Cell::Bytes(vec![3]),
+        ]
+    );
+}
@@ -0,0 +1,151 @@
+use visit_rs::path::{
+    PathSegment, PathVisitor, VisitFieldsPathed, VisitPath, VisitVariantFieldsPathed,
+};
+use visit_rs::{Named, Visit, VisitFields, VisitVariants, Visitor};
+
+/// Collects validation errors along with where they occurred
+#[derive(Default)]
+struct Validator {
+    path: VisitPath,
+    errors: Vec<String>,
+}
+
+impl Visitor for Validator {
+    type Result = ();
+}
+
+impl PathVisitor for Validator {
+    fn path(&self) -> &VisitPath {
+        &self.path
+    }
+    fn path_mut(&mut self) -> &mut VisitPath {
+        &mut self.path
+    }
+}
+
+impl Validator {
+    fn error(&mut self, message: &str) {
+        self.errors.push(format!("{}: {message}", self.path));
+    }
+}
+
+impl<'a> Visit<Validator> for Named<'a, String> {
+    fn visit(&self, visitor: &mut Validator) {
+        if self.value.is_empty() {
+            visitor.error("must not be empty");
+        }
+    }
+}
+
+impl<'a> Visit<Validator> for Named<'a, u16> {
+    fn visit(&self, visitor: &mut Validator) {
+        if *self.value == 0 {
+            visitor.error("must not be zero");
+        }
+    }
+}
+
+impl Visit<Validator> for Server {
+    fn visit(&self, visitor: &mut Validator) {
+        self.visit_fields_pathed(visitor).for_each(drop)
+    }
+}
+
+impl<'a> Visit<Validator> for Named<'a, Vec<Server>> {
+    fn visit(&self, visitor: &mut Validator) {
+        visitor.visit_seq_at(self.value).for_each(drop)
+    }
+}
+
+impl<'a> Visit<Validator> for Named<'a, Mode> {
+    fn visit(&self, visitor: &mut Validator) {
+        self.value
+            .visit_variant_fields_pathed(visitor)
+            .for_each(drop)
+    }
+}
+
+#[derive(VisitFields)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    mode: Mode,
+}
+
+#[derive(VisitFields)]
+#[visit(rename_all = "camelCase")]
+struct Server {
+    host_name: String,
+    port: u16,
+}
+
+#[derive(VisitVariants)]
+enum Mode {
+    Plain,
+    Tls { cert: String },
+    Range(u16, u16),
+}
+
+#[derive(VisitFields)]
+struct Pair(String, String);
+
+fn server(host_name: &str, port: u16) -> Server {
+    Server {
+        host_name: host_name.to_string(),
+        port,
+    }
+}
+
+fn validate(config: &Config) -> Vec<String> {
+    let mut validator = Validator::default();
+    validator.path.push(PathSegment::Field("config"));
+    config.visit_fields_pathed(&mut validator).for_each(drop);
+    assert_eq!(validator.path.len(), 1);
+    validator.errors
+}
+
+#[test]
+fn test_nested_paths() {
+    let config = Config {
+        name: String::new(),
+        servers: vec![server("a", 80), server("b", 443), server("", 0)],
+        mode: Mode::Tls {
+            cert: String::new(),
+        },
+    };
+    assert_eq!(
+        validate(&config),
+        vec![
+            "config.name: must not be empty",
+            "config.servers[2].hostName: must not be empty",
+            "config.servers[2].port: must not be zero",
+            "config.mode::Tls.cert: must not be empty",
+        ]
+    );
+}
+
+#[test]
+fn test_tuple_paths() {
+    let config = Config {
+        name: "ok".to_string(),
+        servers: Vec::new(),
+        mode: Mode::Range(1, 0),
+    };
+    assert_eq!(
+        validate(&config),
+        vec!["config.mode::Range.1: must not be zero"]
+    );
+    assert!(
+        Mode::Plain
+            .visit_variant_fields_pathed(&mut Validator::default())
+            .next()
+            .is_none()
+    );
+
+    let mut validator = Validator::default();
+    Pair("x".to_string(), String::new())
+        .visit_fields_pathed(&mut validator)
+        .for_each(drop);
+    assert_eq!(validator.errors, vec!["1: must not be empty"]);
+    assert!(validator.path().is_empty());
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "rayon")]
+
+use std::collections::HashSet;
+
+use rayon::iter::ParallelIterator;
+use visit_rs::rayon::{ParVisitFields, ParVisitFieldsNamed};
+use visit_rs::{Named, Visit, VisitFields, Visitor};
+
+/// A subsystem health check that reports how many problems it found
+struct Check {
+    problems: usize,
+}
+
+/// Counts the problems found by each check
+#[derive(Clone)]
+struct HealthCheck;
+
+impl Visitor for HealthCheck {
+    type Result = usize;
+}
+
+impl Visit<HealthCheck> for Check {
+    fn visit(&self, _visitor: &mut HealthCheck) -> usize {
+        self.problems
+    }
+}
+
+impl<'a> Visit<HealthCheck> for Named<'a, Check> {
+    fn visit(&self, visitor: &mut HealthCheck) -> usize {
+        self.value.visit(visitor)
+    }
+}
+
+/// Collects the names of failing checks
+#[derive(Clone)]
+struct Failing;
+
+impl Visitor for Failing {
+    type Result = Option<&'static str>;
+}
+
+impl<'a> Visit<Failing> for Named<'a, Check> {
+    fn visit(&self, _visitor: &mut Failing) -> Option<&'static str> {
+        (self.value.problems > 0).then_some(self.name).flatten()
+    }
+}
+
+#[derive(VisitFields)]
+#[visit(rename_all = "kebab-case")]
+struct System {
+    database: Check,
+    #[visit(skip)]
+    _cache: Vec<u8>,
+    message_queue: Check,
+    object_store: Check,
+}
+
+#[derive(VisitFields)]
+struct Empty;
+
+fn system() -> System {
+    System {
+        database: Check { problems: 0 },
+        _cache: Vec::new(),
+        message_queue: Check { problems: 2 },
+        object_store: Check { problems: 3 },
+    }
+}
+
+#[test]
+fn test_par_visit_fields() {
+    let system = system();
+    let results: Vec<_> = system.par_visit_fields(&HealthCheck).collect();
+    assert_eq!(results, vec![0, 2, 3]);
+    assert_eq!(
+        system
+            .par_visit_fields(&HealthCheck)
+            .reduce(|| 0, |a, b| a + b),
+        5
+    );
+    assert_eq!(Empty.par_visit_fields(&HealthCheck).count(), 0);
+}
+
+#[test]
+fn test_par_visit_fields_named() {
+    let system = system();
+    let failing: HashSet<_> = system
+        .par_visit_fields_named(&Failing)
+        .flatten_iter()
+        .collect();
+    assert_eq!(failing, HashSet::from(["message-queue", "object-store"]));
+    assert_eq!(
+        system.par_visit_fields_named(&HealthCheck).sum::<usize>(),
+        5
+    );
+}
+
+#[derive(VisitFields)]
+struct Cluster {
+    gateway: Check,
+    #[visit(flatten)]
+    system: System,
+}
+
+#[test]
+fn test_par_visit_fields_flatten() {
+    let cluster = Cluster {
+        gateway: Check { problems: 1 },
+        system: system(),
+    };
+    let results: Vec<_> = cluster.par_visit_fields(&HealthCheck).collect();
+    assert_eq!(results, vec![1, 0, 2, 3]);
+    let failing: HashSet<_> = cluster
+        .par_visit_fields_named(&Failing)
+        .flatten_iter()
+        .collect();
+    assert_eq!(
+        failing,
+        HashSet::from(["gateway", "message-queue", "object-store"])
+    );
+}
@@ -1,4 +1,3 @@
-use std::future::Future;
 use std::io::Write;
 
 use futures::TryStreamExt;
@@ -61,17 +60,15 @@ macro_rules! impl_size_visit_primitive {
 impl_size_visit_primitive!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64, bool, char);
 
 impl VisitAsync<SizeVisitor> for String {
-    fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) -> impl Future<Output = ()> + Send + 'a {
-        async move { self.visit(visitor) }
+    async fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) {
+        self.visit(visitor)
     }
 }
 
 impl<T: VisitAsync<SizeVisitor> + Sync> VisitAsync<SizeVisitor> for Vec<T> {
-    fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) -> impl Future<Output = ()> + Send + 'a {
-        async move {
-            for item in self {
-                VisitAsync::visit_async(item, visitor).await;
-            }
+    async fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) {
+        for item in self {
+            VisitAsync::visit_async(item, visitor).await;
         }
     }
 }
@@ -80,8 +77,8 @@ macro_rules! impl_size_visit_async_primitive {
     ($($t:ty),*) => {
         $(
             impl VisitAsync<SizeVisitor> for $t {
-                fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) -> impl Future<Output = ()> + Send + 'a {
-                    async move { self.visit(visitor) }
+                async fn visit_async<'a>(&'a self, visitor: &'a mut SizeVisitor) {
+                    self.visit(visitor)
                 }
             }
         )*
@@ -103,8 +100,8 @@ impl<'a, T> VisitAsync<SizeVisitor> for Named<'a, T>
 where
     T: VisitAsync<SizeVisitor> + Sync,
 {
-    fn visit_async<'b>(&'b self, visitor: &'b mut SizeVisitor) -> impl Future<Output = ()> + Send + 'b {
-        async move { VisitAsync::visit_async(self.value, visitor).await }
+    async fn visit_async<'b>(&'b self, visitor: &'b mut SizeVisitor) {
+        VisitAsync::visit_async(self.value, visitor).await
     }
 }
 
@@ -121,8 +118,8 @@ impl<'a, T> VisitAsync<SizeVisitor> for Covered<'a, T>
 where
     T: VisitAsync<SizeVisitor> + Sync + ?Sized,
 {
-    fn visit_async<'b>(&'b self, visitor: &'b mut SizeVisitor) -> impl Future<Output = ()> + Send + 'b {
-        async move { VisitAsync::visit_async(self.0, visitor).await }
+    async fn visit_async<'b>(&'b self, visitor: &'b mut SizeVisitor) {
+        VisitAsync::visit_async(self.0, visitor).await
     }
 }
 
@@ -134,9 +131,7 @@ impl<T> VisitAsync<SizeVisitor> for Static<T>
 where
     T: VisitAsync<SizeVisitor>,
 {
-    fn visit_async<'a>(&'a self, _visitor: &'a mut SizeVisitor) -> impl Future<Output = ()> + Send + 'a {
-        async move {}
-    }
+    async fn visit_async<'a>(&'a self, _visitor: &'a mut SizeVisitor) {}
 }
 
 struct WriteVisitor<W: Write> {
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use visit_rs::structural::{MapAccess, SeqAccess, StructuralVisitor};
+use visit_rs::{Static, StructInfo, Visit, VisitFields, VisitFieldsStatic, Visitor};
+
+/// Renders values in a compact JSON-like notation
+struct Render(String);
+
+impl Visitor for Render {
+    type Result = ();
+}
+
+impl StructuralVisitor for Render {
+    fn visit_option<T: Visit<Self> + ?Sized>(&mut self, value: Option<&T>) {
+        match value {
+            Some(value) => value.visit(self),
+            None => self.0.push_str("null"),
+        }
+    }
+    fn visit_seq<S: SeqAccess<Self>>(&mut self, mut seq: S) {
+        self.0.push('[');
+        let mut first = true;
+        loop {
+            let len = self.0.len();
+            if !first {
+                self.0.push(',');
+            }
+            if seq.visit_next(self).is_none() {
+                self.0.truncate(len);
+                break;
+            }
+            first = false;
+        }
+        self.0.push(']');
+    }
+    fn visit_map<M: MapAccess<Self>>(&mut self, mut map: M) {
+        self.0.push('{');
+        while map.visit_next_key(self).is_some() {
+            self.0.push(':');
+            map.visit_next_value(self);
+            self.0.push(',');
+        }
+        if self.0.ends_with(',') {
+            self.0.pop();
+        }
+        self.0.push('}');
+    }
+    fn visit_tuple<S: SeqAccess<Self>>(&mut self, mut tuple: S) {
+        write!(self.0, "{}(", tuple.size_hint().unwrap_or_default()).unwrap();
+        while tuple.visit_next(self).is_some() {
+            self.0.push(' ');
+        }
+        self.0.push(')');
+    }
+}
+
+visit_rs::impl_structural_visit!(Render);
+
+impl Visit<Render> for u32 {
+    fn visit(&self, visitor: &mut Render) {
+        write!(visitor.0, "{self}").unwrap();
+    }
+}
+
+impl Visit<Render> for str {
+    fn visit(&self, visitor: &mut Render) {
+        write!(visitor.0, "{self:?}").unwrap();
+    }
+}
+
+impl Visit<Render> for String {
+    fn visit(&self, visitor: &mut Render) {
+        self.as_str().visit(visitor)
+    }
+}
+
+impl Visit<Render> for Static<u32> {
+    fn visit(&self, visitor: &mut Render) {
+        visitor.0.push_str("u32");
+    }
+}
+
+impl Visit<Render> for Static<str> {
+    fn visit(&self, visitor: &mut Render) {
+        visitor.0.push_str("string");
+    }
+}
+
+impl Visit<Render> for Static<String> {
+    fn visit(&self, visitor: &mut Render) {
+        Static::<str>::new().visit(visitor)
+    }
+}
+
+fn render<T: Visit<Render> + ?Sized>(value: &T) -> String {
+    let mut visitor = Render(String::new());
+    value.visit(&mut visitor);
+    visitor.0
+}
+
+/// Counts leaf values, generic over where the count is reported
+struct Leaves<W> {
+    count: usize,
+    _out: W,
+}
+
+impl<W> Visitor for Leaves<W> {
+    type Result = ();
+}
+
+impl<W> StructuralVisitor for Leaves<W> {
+    fn visit_option<T: Visit<Self> + ?Sized>(&mut self, value: Option<&T>) {
+        if let Some(value) = value {
+            value.visit(self)
+        }
+    }
+    fn visit_seq<S: SeqAccess<Self>>(&mut self, mut seq: S) {
+        while seq.visit_next(self).is_some() {}
+    }
+    fn visit_map<M: MapAccess<Self>>(&mut self, mut map: M) {
+        while map.visit_next_key(self).is_some() {
+            map.visit_next_value(self);
+        }
+    }
+}
+
+visit_rs::impl_structural_visit!(impl<W> Leaves<W>);
+
+impl<W> Visit<Leaves<W>> for u32 {
+    fn visit(&self, visitor: &mut Leaves<W>) {
+        visitor.count += 1;
+    }
+}
+
+#[derive(VisitFields)]
+struct Inventory {
+    tags: Vec<Option<u32>>,
+    stock: BTreeMap<String, (u32, Option<String>)>,
+    owner: Arc<Option<Cow<'static, str>>>,
+}
+
+#[test]
+fn test_containers() {
+    assert_eq!(render(&vec![Some(1u32), None]), "[1,null]");
+    assert_eq!(render(&Vec::<u32>::new()), "[]");
+    assert_eq!(render(&[1u32, 2, 3][..]), "[1,2,3]");
+    assert_eq!(render(&[[1u32], [2]]), "[[1],[2]]");
+    assert_eq!(render(&HashSet::from([7u32])), "[7]");
+    assert_eq!(
+        render(&BTreeMap::from([
+            ("a".to_string(), 1u32),
+            ("b".to_string(), 2)
+        ])),
+        r#"{"a":1,"b":2}"#
+    );
+    assert_eq!(render(&(1u32, "x".to_string())), r#"2(1 "x" )"#);
+}
+
+#[test]
+fn test_smart_pointers() {
+    assert_eq!(render(&Rc::new(vec![5u32])), "[5]");
+    assert_eq!(render(&Arc::<str>::from("arc")), r#""arc""#);
+    assert_eq!(render(&Cow::<str>::Borrowed("cow")), r#""cow""#);
+    assert_eq!(render(&Static::<Box<&u32>>::new()), "u32");
+}
+
+#[test]
+fn test_derived_fields() {
+    let inventory = Inventory {
+        tags: vec![Some(1), None],
+        stock: BTreeMap::from([("bolt".to_string(), (4, None))]),
+        owner: Arc::new(Some(Cow::Borrowed("me"))),
+    };
+    let mut visitor = Render(String::new());
+    inventory.visit_fields(&mut visitor).for_each(drop);
+    assert_eq!(visitor.0, r#"[1,null]{"bolt":2(4 null )}"me""#);
+
+    let mut visitor = Leaves { count: 0, _out: () };
+    vec![Some(1u32), None, Some(3)].visit(&mut visitor);
+    BTreeMap::from([(1u32, 2u32)]).visit(&mut visitor);
+    assert_eq!(visitor.count, 4);
+}
+
+#[test]
+fn test_static() {
+    assert_eq!(render(&Static::<Vec<Option<u32>>>::new()), "[u32]");
+    assert_eq!(render(&Static::<[u32; 4]>::new()), "[u32]");
+    assert_eq!(
+        render(&Static::<BTreeMap<String, Box<u32>>>::new()),
+        "{string:u32}"
+    );
+    assert_eq!(render(&Static::<(u32, String)>::new()), "2(u32 string )");
+
+    assert_eq!(Inventory::DATA.field_count, 3);
+    let mut visitor = Render(String::new());
+    Inventory::visit_fields_static(&mut visitor).for_each(drop);
+    assert_eq!(visitor.0, r#"[u32]{string:2(u32 string )}string"#);
+}
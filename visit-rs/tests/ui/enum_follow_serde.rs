@@ -0,0 +1,14 @@
+use visit_rs::VisitVariants;
+
+// serde's skips on variant fields would be silently kept
+#[derive(VisitVariants)]
+#[visit(follow_serde)]
+enum Event {
+    Login {
+        user: String,
+        #[visit(skip_serializing)]
+        password: String,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+use visit_rs::{Build, FromFields, Named, Source, Static, VisitFields, Visitor};
+
+struct Strings(Vec<String>);
+
+impl Visitor for Strings {
+    type Result = ();
+}
+
+impl Source for Strings {
+    type Error = ();
+}
+
+impl<'a> Build<Strings> for Named<'a, Static<String>> {
+    type Output = String;
+    fn build(&self, source: &mut Strings) -> Result<String, ()> {
+        source.0.pop().ok_or(())
+    }
+}
+
+/// `File` has no `Default` to fill the skipped field with
+#[derive(VisitFields)]
+struct LogFile {
+    path: String,
+    #[visit(skip)]
+    file: std::fs::File,
+}
+
+fn main() {
+    let _ = LogFile::from_fields(&mut Strings(vec!["log.txt".to_string()]));
+}
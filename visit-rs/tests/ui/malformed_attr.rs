@@ -0,0 +1,9 @@
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+#[visit = "x"]
+struct Config {
+    max_size: u64,
+}
+
+fn main() {}
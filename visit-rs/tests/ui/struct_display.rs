@@ -0,0 +1,9 @@
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+#[visit(display, from_str)]
+struct Config {
+    max_size: u64,
+}
+
+fn main() {}
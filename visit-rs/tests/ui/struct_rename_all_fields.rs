@@ -0,0 +1,9 @@
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+#[visit(rename_all_fields = "camelCase")]
+struct Config {
+    max_size: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use visit_rs::VisitVariants;
+
+#[derive(VisitVariants)]
+#[visit(unit_variants)]
+enum Shape {
+    Empty,
+    Circle(u32),
+}
+
+fn main() {}
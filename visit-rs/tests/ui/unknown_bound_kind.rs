@@ -0,0 +1,9 @@
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+#[visit(bound(visti = "T: Clone"))]
+struct Config<T> {
+    value: T,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use visit_rs::VisitFields;
+
+#[derive(VisitFields)]
+struct Config {
+    #[visit(renmae)]
+    max_size: u64,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use visit_rs::VisitVariants;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(VisitVariants)]
+enum Shape {
+    Circle {
+        #[visit(flatten)]
+        center: Point,
+        radius: i32,
+    },
+}
+
+fn main() {}
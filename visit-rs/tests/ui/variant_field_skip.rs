@@ -0,0 +1,12 @@
+use visit_rs::VisitVariants;
+
+#[derive(VisitVariants)]
+enum Event {
+    Login {
+        user: String,
+        #[visit(skip)]
+        password: String,
+    },
+}
+
+fn main() {}
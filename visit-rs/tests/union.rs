@@ -0,0 +1,90 @@
+use futures::StreamExt;
+use visit_rs::{
+    Named, Static, StructInfo, Visit, VisitAsync, VisitFieldStatic, VisitFields, VisitFieldsStatic,
+    VisitFieldsStaticNamed, VisitFieldsStaticNamedAsync, Visitor,
+};
+
+#[derive(VisitFields)]
+#[visit(rename_all = "camelCase")]
+#[repr(C)]
+#[allow(dead_code)]
+union RawValue {
+    as_int: i64,
+    as_float: f64,
+    #[visit(skip)]
+    as_bytes: [u8; 8],
+}
+
+/// Describes the layout of the types it visits
+#[derive(Default)]
+struct Layout(Vec<String>);
+
+impl Visitor for Layout {
+    type Result = ();
+}
+
+impl<T> Visit<Layout> for Static<T> {
+    fn visit(&self, visitor: &mut Layout) {
+        visitor.0.push(format!(
+            "{}@{}",
+            std::any::type_name::<T>(),
+            std::mem::size_of::<T>()
+        ));
+    }
+}
+
+impl<T> VisitAsync<Layout> for Static<T> {
+    async fn visit_async<'a>(&'a self, visitor: &'a mut Layout) {
+        self.visit(visitor)
+    }
+}
+
+impl<'a, T: Visit<Layout>> Visit<Layout> for Named<'a, T> {
+    fn visit(&self, visitor: &mut Layout) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit(visitor)
+    }
+}
+
+impl<'a, T: VisitAsync<Layout> + Sync> VisitAsync<Layout> for Named<'a, T> {
+    async fn visit_async<'b>(&'b self, visitor: &'b mut Layout) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_async(visitor).await
+    }
+}
+
+#[test]
+fn test_union_info() {
+    let data = RawValue::DATA;
+    assert_eq!(data.name, "RawValue");
+    assert!(data.named_fields);
+    assert_eq!(data.field_count, 2);
+    let names: Vec<_> = RawValue::FIELDS.iter().map(|field| field.name).collect();
+    assert_eq!(names, vec![Some("asInt"), Some("asFloat"), Some("asBytes")]);
+    assert!(RawValue::FIELDS[2].skip);
+}
+
+#[test]
+fn test_union_static_visit() {
+    let mut layout = Layout::default();
+    RawValue::visit_fields_static(&mut layout).for_each(drop);
+    assert_eq!(layout.0, vec!["i64@8", "f64@8"]);
+
+    let mut layout = Layout::default();
+    RawValue::visit_fields_static_named(&mut layout).for_each(drop);
+    assert_eq!(layout.0, vec!["asInt:", "i64@8", "asFloat:", "f64@8"]);
+
+    let mut layout = Layout::default();
+    assert!(RawValue::visit_field_by_name_static("asFloat", &mut layout).is_some());
+    assert!(RawValue::visit_field_by_name_static("asBytes", &mut layout).is_none());
+    assert_eq!(layout.0, vec!["asFloat:", "f64@8"]);
+}
+
+#[tokio::test]
+async fn test_union_static_visit_async() {
+    let mut layout = Layout::default();
+    RawValue::visit_fields_static_named_async(&mut layout)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(layout.0, vec!["asInt:", "i64@8", "asFloat:", "f64@8"]);
+}
@@ -0,0 +1,406 @@
+use futures::StreamExt;
+use visit_rs::{
+    Covered, Named, NamedMut, Static, Visit, VisitAsync, VisitAsyncLocal, VisitFields,
+    VisitFieldsAsync, VisitFieldsAsyncLocal, VisitFieldsCovered, VisitFieldsMut, VisitFieldsNamed,
+    VisitFieldsNamedAsync, VisitFieldsNamedAsyncLocal, VisitFieldsNamedMut, VisitFieldsOwned,
+    VisitFieldsStatic, VisitFieldsStaticNamed, VisitMut, VisitOwned, VisitVariantFieldsAsync,
+    VisitVariantFieldsAsyncLocal, VisitVariantFieldsMut, VisitVariantFieldsNamed,
+    VisitVariantFieldsNamedAsyncLocal, VisitVariantFieldsNamedMut, VisitVariantFieldsOwned,
+    VisitVariants, Visitor,
+};
+
+/// Stands in for a foreign type with no `Visit` impls of its own
+struct Timestamp(u64);
+
+/// Visits a `Timestamp` as its formatted string
+mod iso8601 {
+    use visit_rs::{Static, Visit, VisitAsync, VisitMut, VisitOwned, Visitor};
+
+    use super::Timestamp;
+
+    fn format(value: &Timestamp) -> String {
+        format!("@{}", value.0)
+    }
+
+    pub fn visit<V: Visitor>(value: &Timestamp, visitor: &mut V) -> V::Result
+    where
+        String: Visit<V>,
+    {
+        format(value).visit(visitor)
+    }
+
+    pub async fn visit_async<V>(value: &Timestamp, visitor: &mut V) -> V::Result
+    where
+        V: Visitor + Send,
+        V::Result: Send,
+        String: VisitAsync<V>,
+    {
+        format(value).visit_async(visitor).await
+    }
+
+    pub fn visit_static<V: Visitor>(visitor: &mut V) -> V::Result
+    where
+        Static<String>: Visit<V>,
+    {
+        Static::<String>::new().visit(visitor)
+    }
+
+    pub async fn visit_static_async<V>(visitor: &mut V) -> V::Result
+    where
+        V: Visitor + Send,
+        V::Result: Send,
+        Static<String>: VisitAsync<V>,
+    {
+        Static::<String>::new().visit_async(visitor).await
+    }
+
+    /// Visits the formatted string, keeping any change made to it
+    pub fn visit_mut<V: Visitor>(value: &mut Timestamp, visitor: &mut V) -> V::Result
+    where
+        String: VisitMut<V>,
+    {
+        let mut formatted = format(value);
+        let res = formatted.visit_mut(visitor);
+        if let Some(secs) = formatted.strip_prefix('@').and_then(|s| s.parse().ok()) {
+            value.0 = secs;
+        }
+        res
+    }
+
+    pub fn visit_owned<V: Visitor>(value: Timestamp, visitor: &mut V) -> V::Result
+    where
+        String: VisitOwned<V>,
+    {
+        format(&value).visit_owned(visitor)
+    }
+}
+
+/// Stands in for a foreign identifier
+struct Uuid([u8; 2]);
+
+fn visit_uuid<V: Visitor>(value: &Uuid, visitor: &mut V) -> V::Result
+where
+    String: Visit<V>,
+{
+    format!("{:02x}{:02x}", value.0[0], value.0[1]).visit(visitor)
+}
+
+/// `visit_with` only replaces `visit`, so async visits still go through this
+impl VisitAsync<Log> for Uuid {
+    async fn visit_async<'a>(&'a self, visitor: &'a mut Log) {
+        visitor.0.push(format!("{:?}", self.0));
+    }
+}
+
+impl VisitAsyncLocal<Log> for Uuid {
+    async fn visit_async_local<'a>(&'a self, visitor: &'a mut Log) {
+        self.visit_async(visitor).await
+    }
+}
+
+impl VisitMut<Log> for Uuid {
+    fn visit_mut(&mut self, visitor: &mut Log) {
+        visitor.0.push(format!("{:?}", self.0));
+    }
+}
+
+impl VisitOwned<Log> for Uuid {
+    fn visit_owned(self, visitor: &mut Log) {
+        visitor.0.push(format!("{:?}", self.0));
+    }
+}
+
+#[derive(VisitFields)]
+#[visit(owned)]
+struct Event {
+    title: String,
+    #[visit(with = "iso8601")]
+    #[visit(bound(
+        visit = "String: Visit<__visit_rs__V>, Static<String>: Visit<__visit_rs__V>",
+        visit_async = "String: VisitAsync<__visit_rs__V>, Static<String>: VisitAsync<__visit_rs__V>",
+        visit_async_local = "__visit_rs__V: Send, <__visit_rs__V as Visitor>::Result: Send, String: VisitAsync<__visit_rs__V>",
+        visit_mut = "String: VisitMut<__visit_rs__V>",
+        visit_owned = "String: VisitOwned<__visit_rs__V>"
+    ))]
+    at: Timestamp,
+    #[visit(visit_with = "visit_uuid")]
+    #[visit(bound(visit = "String: Visit<__visit_rs__V>"))]
+    id: Uuid,
+}
+
+#[derive(VisitVariants)]
+#[visit(owned)]
+enum Schedule {
+    Once(
+        #[visit(with = "iso8601")]
+        #[visit(bound(
+            visit = "String: Visit<__visit_rs__V>, Static<String>: Visit<__visit_rs__V>",
+            visit_async = "String: VisitAsync<__visit_rs__V>, Static<String>: VisitAsync<__visit_rs__V>",
+            visit_async_local = "__visit_rs__V: Send, <__visit_rs__V as Visitor>::Result: Send, String: VisitAsync<__visit_rs__V>",
+            visit_mut = "String: VisitMut<__visit_rs__V>",
+            visit_owned = "String: VisitOwned<__visit_rs__V>"
+        ))]
+        Timestamp,
+    ),
+    Never,
+}
+
+/// Records what it visits
+#[derive(Default)]
+struct Log(Vec<String>);
+
+impl Visitor for Log {
+    type Result = ();
+}
+
+impl Visit<Log> for String {
+    fn visit(&self, visitor: &mut Log) {
+        visitor.0.push(self.clone());
+    }
+}
+
+impl VisitAsync<Log> for String {
+    async fn visit_async<'a>(&'a self, visitor: &'a mut Log) {
+        visitor.0.push(self.clone());
+    }
+}
+
+impl<T: ?Sized> Visit<Log> for Static<T> {
+    fn visit(&self, visitor: &mut Log) {
+        let name = std::any::type_name::<T>();
+        visitor
+            .0
+            .push(name.rsplit("::").next().unwrap_or(name).to_string());
+    }
+}
+
+impl<T: ?Sized> VisitAsync<Log> for Static<T> {
+    async fn visit_async<'a>(&'a self, visitor: &'a mut Log) {
+        self.visit(visitor)
+    }
+}
+
+impl<'a, T: Visit<Log> + ?Sized> Visit<Log> for Named<'a, T> {
+    fn visit(&self, visitor: &mut Log) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit(visitor);
+    }
+}
+
+impl<'a, T: VisitAsync<Log> + Sync + ?Sized> VisitAsync<Log> for Named<'a, T> {
+    async fn visit_async<'b>(&'b self, visitor: &'b mut Log) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_async(visitor).await;
+    }
+}
+
+impl VisitAsyncLocal<Log> for String {
+    async fn visit_async_local<'a>(&'a self, visitor: &'a mut Log) {
+        self.visit(visitor)
+    }
+}
+
+/// Redacts the digits of what it records
+impl VisitMut<Log> for String {
+    fn visit_mut(&mut self, visitor: &mut Log) {
+        visitor.0.push(self.clone());
+        *self = self.replace(|c: char| c.is_ascii_digit(), "0");
+    }
+}
+
+impl VisitOwned<Log> for String {
+    fn visit_owned(self, visitor: &mut Log) {
+        visitor.0.push(self);
+    }
+}
+
+impl<'a, T: VisitAsyncLocal<Log> + ?Sized> VisitAsyncLocal<Log> for Named<'a, T> {
+    async fn visit_async_local<'b>(&'b self, visitor: &'b mut Log) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_async_local(visitor).await;
+    }
+}
+
+impl<'a, T: VisitMut<Log> + ?Sized> VisitMut<Log> for NamedMut<'a, T> {
+    fn visit_mut(&mut self, visitor: &mut Log) {
+        visitor.0.push(format!("{}:", self.name.unwrap_or("?")));
+        self.value.visit_mut(visitor);
+    }
+}
+
+impl<'a, T: Visit<Log> + ?Sized> Visit<Log> for Covered<'a, T> {
+    fn visit(&self, visitor: &mut Log) {
+        visitor.0.push("covered".to_string());
+        self.0.visit(visitor);
+    }
+}
+
+fn event() -> Event {
+    Event {
+        title: "launch".to_string(),
+        at: Timestamp(1700),
+        id: Uuid([0xbe, 0xef]),
+    }
+}
+
+#[test]
+fn test_visit_with() {
+    let mut log = Log::default();
+    event().visit_fields(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["launch", "@1700", "beef"]);
+
+    let mut log = Log::default();
+    event().visit_fields_named(&mut log).for_each(drop);
+    assert_eq!(
+        log.0,
+        vec!["title:", "launch", "at:", "@1700", "id:", "beef"]
+    );
+
+    let mut log = Log::default();
+    event().visit_fields_covered(&mut log).for_each(drop);
+    assert_eq!(
+        log.0,
+        vec!["covered", "launch", "covered", "@1700", "covered", "beef"]
+    );
+}
+
+#[test]
+fn test_visit_with_static() {
+    let mut log = Log::default();
+    Event::visit_fields_static(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["String", "String", "Uuid"]);
+
+    let mut log = Log::default();
+    Event::visit_fields_static_named(&mut log).for_each(drop);
+    assert_eq!(
+        log.0,
+        vec!["title:", "String", "at:", "String", "id:", "Uuid"]
+    );
+}
+
+#[tokio::test]
+async fn test_visit_with_async() {
+    let event = event();
+
+    let mut log = Log::default();
+    event
+        .visit_fields_async(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(log.0, vec!["launch", "@1700", "[190, 239]"]);
+
+    let mut log = Log::default();
+    event
+        .visit_fields_named_async(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(
+        log.0,
+        vec!["title:", "launch", "at:", "@1700", "id:", "[190, 239]"]
+    );
+}
+
+#[tokio::test]
+async fn test_visit_with_variant() {
+    let schedule = Schedule::Once(Timestamp(42));
+
+    let mut log = Log::default();
+    schedule.visit_variant_fields_named(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["?:", "@42"]);
+
+    let mut log = Log::default();
+    schedule
+        .visit_variant_fields_async(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(log.0, vec!["@42"]);
+
+    let mut log = Log::default();
+    Schedule::Never
+        .visit_variant_fields_named(&mut log)
+        .for_each(drop);
+    assert!(log.0.is_empty());
+}
+
+#[test]
+fn test_visit_with_mut() {
+    let mut event = event();
+
+    let mut log = Log::default();
+    event.visit_fields_mut(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["launch", "@1700", "[190, 239]"]);
+    assert_eq!(event.at.0, 0);
+
+    event.at = Timestamp(1700);
+    let mut log = Log::default();
+    event.visit_fields_named_mut(&mut log).for_each(drop);
+    assert_eq!(
+        log.0,
+        vec!["title:", "launch", "at:", "@1700", "id:", "[190, 239]"]
+    );
+    assert_eq!(event.at.0, 0);
+
+    let mut schedule = Schedule::Once(Timestamp(42));
+    let mut log = Log::default();
+    schedule.visit_variant_fields_mut(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["@42"]);
+    assert!(matches!(schedule, Schedule::Once(Timestamp(0))));
+
+    let mut schedule = Schedule::Once(Timestamp(42));
+    let mut log = Log::default();
+    schedule
+        .visit_variant_fields_named_mut(&mut log)
+        .for_each(drop);
+    assert_eq!(log.0, vec!["?:", "@42"]);
+    assert!(matches!(schedule, Schedule::Once(Timestamp(0))));
+}
+
+#[test]
+fn test_visit_with_owned() {
+    let mut log = Log::default();
+    event().visit_fields_owned(&mut log).for_each(drop);
+    assert_eq!(log.0, vec!["launch", "@1700", "[190, 239]"]);
+
+    let mut log = Log::default();
+    Schedule::Once(Timestamp(42))
+        .visit_variant_fields_owned(&mut log)
+        .for_each(drop);
+    assert_eq!(log.0, vec!["@42"]);
+}
+
+#[tokio::test]
+async fn test_visit_with_async_local() {
+    let event = event();
+
+    let mut log = Log::default();
+    event
+        .visit_fields_async_local(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(log.0, vec!["launch", "@1700", "[190, 239]"]);
+
+    let mut log = Log::default();
+    event
+        .visit_fields_named_async_local(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(
+        log.0,
+        vec!["title:", "launch", "at:", "@1700", "id:", "[190, 239]"]
+    );
+
+    let schedule = Schedule::Once(Timestamp(42));
+    let mut log = Log::default();
+    schedule
+        .visit_variant_fields_async_local(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(log.0, vec!["@42"]);
+
+    let mut log = Log::default();
+    schedule
+        .visit_variant_fields_named_async_local(&mut log)
+        .for_each(|_| async {})
+        .await;
+    assert_eq!(log.0, vec!["?:", "@42"]);
+}